@@ -0,0 +1,203 @@
+//! Animation metadata for QA: duration, frames, tracks, compression and annotations of
+//! every animation in a folder, read through serde_hkx.
+
+use crate::hkx_document::{HkxDocument, HkxObject, HkxValue};
+use crate::packfile::PackfileHeader;
+use anyhow::Result;
+use std::cmp::Ordering;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone)]
+pub struct AnimationInfo {
+    pub file: PathBuf,
+    /// `LE`, `SE` or `XML`
+    pub format: &'static str,
+    pub duration: f32,
+    pub frames: Option<usize>,
+    pub tracks: usize,
+    pub compression: String,
+    pub annotations: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportColumn {
+    File,
+    Format,
+    Duration,
+    Frames,
+    Tracks,
+    Compression,
+    Annotations,
+}
+
+impl ReportColumn {
+    pub const ALL: [Self; 7] = [
+        Self::File,
+        Self::Format,
+        Self::Duration,
+        Self::Frames,
+        Self::Tracks,
+        Self::Compression,
+        Self::Annotations,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::File => "File",
+            Self::Format => "Format",
+            Self::Duration => "Duration (s)",
+            Self::Frames => "Frames",
+            Self::Tracks => "Tracks",
+            Self::Compression => "Compression",
+            Self::Annotations => "Annotations",
+        }
+    }
+
+    pub fn compare(&self, a: &AnimationInfo, b: &AnimationInfo) -> Ordering {
+        match self {
+            Self::File => a.file.cmp(&b.file),
+            Self::Format => a.format.cmp(b.format),
+            Self::Duration => a.duration.total_cmp(&b.duration),
+            Self::Frames => a.frames.cmp(&b.frames),
+            Self::Tracks => a.tracks.cmp(&b.tracks),
+            Self::Compression => a.compression.cmp(&b.compression),
+            Self::Annotations => a.annotations.cmp(&b.annotations),
+        }
+    }
+
+    /// Cell text, shared by the table and the CSV export
+    pub fn value(&self, info: &AnimationInfo, folder: &Path) -> String {
+        match self {
+            Self::File => info
+                .file
+                .strip_prefix(folder)
+                .unwrap_or(&info.file)
+                .to_string_lossy()
+                .to_string(),
+            Self::Format => info.format.to_string(),
+            Self::Duration => format!("{:.3}", info.duration),
+            Self::Frames => info.frames.map(|f| f.to_string()).unwrap_or_default(),
+            Self::Tracks => info.tracks.to_string(),
+            Self::Compression => info.compression.clone(),
+            Self::Annotations => info.annotations.to_string(),
+        }
+    }
+}
+
+/// Every animation in one file; files without animations give an empty list
+pub async fn read_animations(path: &Path) -> Result<Vec<AnimationInfo>> {
+    let format = file_format(path);
+    let document = HkxDocument::load(path).await?;
+    Ok(document
+        .objects
+        .iter()
+        .filter(|object| object.param("numberOfTransformTracks").is_some())
+        .map(|animation| describe_animation(path, format, animation))
+        .collect())
+}
+
+fn describe_animation(path: &Path, format: &'static str, animation: &HkxObject) -> AnimationInfo {
+    let number = |name: &str| {
+        animation
+            .text(name)
+            .and_then(|value| value.parse::<usize>().ok())
+    };
+    let tracks = number("numberOfTransformTracks").unwrap_or_default();
+    let frames = number("numFrames").or_else(|| {
+        // Uncompressed animations store one transform per track and frame
+        let transforms = animation.array_len("transforms")?;
+        (tracks > 0).then(|| transforms / tracks)
+    });
+    let annotations = match animation
+        .param("annotationTracks")
+        .map(|param| &param.value)
+    {
+        Some(HkxValue::Objects(tracks)) => tracks
+            .iter()
+            .filter_map(|track| track.array_len("annotations"))
+            .sum(),
+        _ => 0,
+    };
+
+    AnimationInfo {
+        file: path.to_path_buf(),
+        format,
+        duration: animation
+            .text("duration")
+            .and_then(|duration| duration.parse().ok())
+            .unwrap_or_default(),
+        frames,
+        tracks,
+        compression: compression_label(animation.class.as_deref().unwrap_or_default()),
+        annotations,
+    }
+}
+
+fn compression_label(class: &str) -> String {
+    match class {
+        "hkaSplineCompressedAnimation" => "Spline",
+        "hkaInterleavedUncompressedAnimation" => "Interleaved",
+        "hkaDeltaCompressedAnimation" => "Delta",
+        "hkaWaveletCompressedAnimation" => "Wavelet",
+        "hkaQuantizedAnimation" => "Quantized",
+        other => other,
+    }
+    .to_string()
+}
+
+/// `LE` or `SE` from a packfile's pointer size, `XML` for anything else serde_hkx reads
+fn file_format(path: &Path) -> &'static str {
+    let header = fs::read(path)
+        .ok()
+        .and_then(|bytes| PackfileHeader::parse(&bytes).ok());
+    match header.map(|header| header.pointer_size) {
+        Some(4) => "LE",
+        Some(8) => "SE",
+        Some(_) => "?",
+        None => "XML",
+    }
+}
+
+/// CSV of the rows in `rows` order, with paths relative to `folder`
+pub fn to_csv(rows: &[AnimationInfo], folder: &Path) -> String {
+    let mut csv = ReportColumn::ALL
+        .iter()
+        .map(|column| csv_field(column.label()))
+        .collect::<Vec<_>>()
+        .join(",");
+    csv.push('\n');
+    for row in rows {
+        let fields = ReportColumn::ALL
+            .iter()
+            .map(|column| csv_field(&column.value(row, folder)))
+            .collect::<Vec<_>>();
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Files in `folder` and its subfolders that may hold animations
+pub fn animation_files(folder: &Path) -> Vec<PathBuf> {
+    walkdir::WalkDir::new(folder)
+        .follow_links(true)
+        .into_iter()
+        .flatten()
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .filter(|path| {
+            path.extension().is_some_and(|ext| {
+                ext.eq_ignore_ascii_case("hkx") || ext.eq_ignore_ascii_case("xml")
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,269 @@
+//! Annotation tracks of an animation (`hkaAnnotationTrack`): the timed text events such as
+//! `SoundPlay.*`, `HitFrame` or `weaponSwing` the game reacts to.
+//!
+//! The text form follows hkanno: one `<time> <text>` line per annotation, `#` comments, and
+//! a `# track: <name>` line before each track's annotations.
+
+use crate::hkx_document::{HkxDocument, HkxObject, HkxParam, HkxValue};
+use crate::OutputFormat;
+use anyhow::{bail, Context as AnyhowContext, Result};
+use std::fs;
+use std::path::Path;
+use tokio::process::Command;
+
+const TRACK_PREFIX: &str = "# track:";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Annotation {
+    pub time: f32,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnnotationTrack {
+    pub name: String,
+    pub annotations: Vec<Annotation>,
+}
+
+/// An animation loaded for editing its annotations
+pub struct AnimationAnnotations {
+    pub document: HkxDocument,
+    /// Index of the `hkaAnimation` object holding `annotationTracks`
+    animation: usize,
+    pub duration: f32,
+    pub tracks: Vec<AnnotationTrack>,
+}
+
+impl AnimationAnnotations {
+    pub async fn load(path: &Path) -> Result<Self> {
+        let document = HkxDocument::load(path).await?;
+        Self::from_document(document).with_context(|| format!("{:?} is not an animation", path))
+    }
+
+    pub fn from_document(document: HkxDocument) -> Result<Self> {
+        let animation = document
+            .objects
+            .iter()
+            .position(|object| object.param("annotationTracks").is_some())
+            .context("File contains no animation with annotation tracks")?;
+        let object = &document.objects[animation];
+        let duration = object
+            .text("duration")
+            .and_then(|duration| duration.parse().ok())
+            .unwrap_or_default();
+
+        let tracks = match &object.param("annotationTracks").map(|param| &param.value) {
+            Some(HkxValue::Objects(tracks)) => tracks
+                .iter()
+                .map(|track| AnnotationTrack {
+                    name: track.text("trackName").unwrap_or_default().to_string(),
+                    annotations: match track.param("annotations").map(|param| &param.value) {
+                        Some(HkxValue::Objects(annotations)) => annotations
+                            .iter()
+                            .map(|annotation| Annotation {
+                                time: annotation
+                                    .text("time")
+                                    .and_then(|time| time.parse().ok())
+                                    .unwrap_or_default(),
+                                text: annotation.text("text").unwrap_or_default().to_string(),
+                            })
+                            .collect(),
+                        _ => Vec::new(),
+                    },
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        Ok(Self {
+            document,
+            animation,
+            duration,
+            tracks,
+        })
+    }
+
+    /// Move every annotation at or after `from` by `offset` seconds, keeping them inside the
+    /// animation
+    pub fn shift(&mut self, from: f32, offset: f32) {
+        for track in &mut self.tracks {
+            for annotation in &mut track.annotations {
+                if annotation.time >= from {
+                    annotation.time = (annotation.time + offset).clamp(0.0, self.duration);
+                }
+            }
+            sort_track(track);
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = format!("# duration: {:.6}\n", self.duration);
+        for track in &self.tracks {
+            text.push_str(&format!("{} {}\n", TRACK_PREFIX, track.name));
+            for annotation in &track.annotations {
+                text.push_str(&format!("{:.6} {}\n", annotation.time, annotation.text));
+            }
+        }
+        text
+    }
+
+    /// Replace the annotations with ones read by [`Self::to_text`]'s format. Annotations
+    /// before any track line go to the first track, as in hkanno's single-track files.
+    pub fn import_text(&mut self, text: &str) -> Result<()> {
+        let mut tracks: Vec<AnnotationTrack> = Vec::new();
+        for (line_number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if let Some(name) = line.strip_prefix(TRACK_PREFIX) {
+                tracks.push(AnnotationTrack {
+                    name: name.trim().to_string(),
+                    annotations: Vec::new(),
+                });
+                continue;
+            }
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (time, annotation_text) =
+                line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let time: f32 = time
+                .parse()
+                .with_context(|| format!("Line {}: {:?} is not a time", line_number + 1, time))?;
+            if time < 0.0 || time > self.duration + f32::EPSILON {
+                bail!(
+                    "Line {}: {} is outside the animation (0 to {:.6})",
+                    line_number + 1,
+                    time,
+                    self.duration
+                );
+            }
+            if tracks.is_empty() {
+                tracks.push(AnnotationTrack {
+                    name: self
+                        .tracks
+                        .first()
+                        .map(|track| track.name.clone())
+                        .unwrap_or_default(),
+                    annotations: Vec::new(),
+                });
+            }
+            tracks.last_mut().unwrap().annotations.push(Annotation {
+                time,
+                text: annotation_text.trim().to_string(),
+            });
+        }
+
+        // A file listing only the first track leaves the others as they are
+        for track in self.tracks.iter().skip(tracks.len()) {
+            tracks.push(track.clone());
+        }
+        for track in &mut tracks {
+            sort_track(track);
+        }
+        self.tracks = tracks;
+        Ok(())
+    }
+
+    /// The document with the edited tracks written back into the animation
+    pub fn updated_document(&self) -> HkxDocument {
+        let mut document = self.document.clone();
+        let tracks = self
+            .tracks
+            .iter()
+            .map(|track| HkxObject {
+                params: vec![
+                    text_param("trackName", track.name.clone()),
+                    HkxParam {
+                        name: "annotations".to_string(),
+                        num_elements: Some(track.annotations.len()),
+                        value: HkxValue::Objects(
+                            track
+                                .annotations
+                                .iter()
+                                .map(|annotation| HkxObject {
+                                    params: vec![
+                                        text_param("time", format!("{:.6}", annotation.time)),
+                                        text_param("text", annotation.text.clone()),
+                                    ],
+                                    ..Default::default()
+                                })
+                                .collect(),
+                        ),
+                    },
+                ],
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+
+        if let Some(param) = document.objects[self.animation]
+            .params
+            .iter_mut()
+            .find(|param| param.name == "annotationTracks")
+        {
+            param.num_elements = Some(tracks.len());
+            param.value = HkxValue::Objects(tracks);
+        }
+        document
+    }
+
+    /// Write an [`Self::updated_document`] as `format`. KF goes through hkxcmd and needs
+    /// the skeleton.
+    pub async fn save_document(
+        document: &HkxDocument,
+        output: &Path,
+        format: OutputFormat,
+        hkxcmd_path: &Path,
+        skeleton: Option<&Path>,
+    ) -> Result<()> {
+        let xml = document.to_xml();
+        if format == OutputFormat::Xml {
+            return fs::write(output, xml).with_context(|| format!("Failed to write {:?}", output));
+        }
+
+        let temp_dir = tempfile::Builder::new()
+            .prefix("hkx_annotations_")
+            .tempdir()
+            .context("Failed to create temporary directory")?;
+        let xml_path = temp_dir.path().join("animation.xml");
+        fs::write(&xml_path, xml).context("Failed to write temporary XML")?;
+
+        match format.try_into() {
+            Ok(out_format) => {
+                serde_hkx_features::convert::tokio::convert(&xml_path, Some(output), out_format)
+                    .await
+                    .with_context(|| format!("serde_hkx failed to write {:?}", output))?;
+            }
+            // KF: hkxcmd exports from the XML against the skeleton
+            Err(_) => {
+                let skeleton = skeleton.context("Saving as KF needs a skeleton")?;
+                let result = Command::new(hkxcmd_path)
+                    .arg("exportkf")
+                    .arg(skeleton)
+                    .arg(&xml_path)
+                    .arg(output)
+                    .output()
+                    .await
+                    .context("Failed to run hkxcmd")?;
+                if !result.status.success() {
+                    bail!(
+                        "hkxcmd failed: {}",
+                        String::from_utf8_lossy(&result.stderr).trim()
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn text_param(name: &str, value: String) -> HkxParam {
+    HkxParam {
+        name: name.to_string(),
+        num_elements: None,
+        value: HkxValue::Text(value),
+    }
+}
+
+fn sort_track(track: &mut AnnotationTrack) {
+    track.annotations.sort_by(|a, b| a.time.total_cmp(&b.time));
+}
@@ -0,0 +1,348 @@
+//! Annotations tab: list, edit, export and import the annotation tracks of an animation.
+
+use crate::annotations::{AnimationAnnotations, Annotation};
+use crate::OutputFormat;
+use anyhow::Result;
+use eframe::egui;
+use egui::{Color32, RichText, Ui};
+use rfd::FileDialog;
+use std::fs;
+use std::path::PathBuf;
+use tokio::sync::oneshot;
+
+const SAVE_FORMATS: [OutputFormat; 4] = [
+    OutputFormat::SkyrimSE,
+    OutputFormat::SkyrimLE,
+    OutputFormat::Xml,
+    OutputFormat::Kf,
+];
+
+pub struct AnnotationsTab {
+    path: Option<PathBuf>,
+    animation: Option<AnimationAnnotations>,
+    /// Errors and results of the last load, import or save
+    status: Option<std::result::Result<String, String>>,
+    shift_from: f32,
+    shift_by: f32,
+    save_format: OutputFormat,
+    skeleton: Option<PathBuf>,
+    hkxcmd_path: PathBuf,
+    load_rx: Option<oneshot::Receiver<Result<AnimationAnnotations>>>,
+    save_rx: Option<oneshot::Receiver<Result<PathBuf>>>,
+    tokio_handle: tokio::runtime::Handle,
+}
+
+impl AnnotationsTab {
+    pub fn new(tokio_handle: tokio::runtime::Handle, hkxcmd_path: PathBuf) -> Self {
+        Self {
+            path: None,
+            animation: None,
+            status: None,
+            shift_from: 0.0,
+            shift_by: 0.0,
+            save_format: OutputFormat::SkyrimSE,
+            skeleton: None,
+            hkxcmd_path,
+            load_rx: None,
+            save_rx: None,
+            tokio_handle,
+        }
+    }
+
+    /// Start loading an animation in the background
+    pub fn open(&mut self, path: PathBuf) {
+        let (load_tx, load_rx) = oneshot::channel();
+        self.load_rx = Some(load_rx);
+        self.animation = None;
+        self.status = None;
+        self.path = Some(path.clone());
+
+        self.tokio_handle.spawn(async move {
+            let _ = load_tx.send(AnimationAnnotations::load(&path).await);
+        });
+    }
+
+    fn poll(&mut self, ui: &Ui) {
+        if let Some(load_rx) = &mut self.load_rx {
+            match load_rx.try_recv() {
+                Ok(Ok(animation)) => {
+                    self.animation = Some(animation);
+                    self.load_rx = None;
+                }
+                Ok(Err(e)) => {
+                    self.status = Some(Err(format!("{:#}", e)));
+                    self.load_rx = None;
+                }
+                Err(oneshot::error::TryRecvError::Empty) => ui.ctx().request_repaint(),
+                Err(oneshot::error::TryRecvError::Closed) => {
+                    self.status = Some(Err("Loading was interrupted".to_string()));
+                    self.load_rx = None;
+                }
+            }
+        }
+        if let Some(save_rx) = &mut self.save_rx {
+            match save_rx.try_recv() {
+                Ok(result) => {
+                    self.status = Some(
+                        result
+                            .map(|path| format!("Saved {}", path.to_string_lossy()))
+                            .map_err(|e| format!("{:#}", e)),
+                    );
+                    self.save_rx = None;
+                }
+                Err(oneshot::error::TryRecvError::Empty) => ui.ctx().request_repaint(),
+                Err(oneshot::error::TryRecvError::Closed) => {
+                    self.status = Some(Err("Saving was interrupted".to_string()));
+                    self.save_rx = None;
+                }
+            }
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui) {
+        self.poll(ui);
+
+        ui.horizontal(|ui| {
+            if ui.button("Open Animation").clicked() {
+                if let Some(file) = FileDialog::new()
+                    .add_filter("HKX/XML files", &["hkx", "xml"])
+                    .pick_file()
+                {
+                    self.open(file);
+                }
+            }
+            if let Some(path) = &self.path {
+                ui.label(path.to_string_lossy());
+            }
+        });
+
+        match &self.status {
+            Some(Ok(message)) => {
+                ui.label(RichText::new(message).color(Color32::from_rgb(100, 200, 100)));
+            }
+            Some(Err(error)) => {
+                ui.label(RichText::new(error).color(Color32::from_rgb(255, 120, 120)));
+            }
+            None => {}
+        }
+
+        if self.load_rx.is_some() {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label("Reading file through serde_hkx...");
+            });
+            return;
+        }
+        if self.animation.is_none() {
+            return;
+        }
+
+        self.render_text_buttons(ui);
+        self.render_shift(ui);
+        ui.separator();
+        self.render_tracks(ui);
+        ui.separator();
+        self.render_save(ui);
+    }
+
+    fn render_text_buttons(&mut self, ui: &mut Ui) {
+        let Some(animation) = &mut self.animation else {
+            return;
+        };
+        ui.horizontal(|ui| {
+            ui.label(format!(
+                "Duration: {:.3}s, {} tracks",
+                animation.duration,
+                animation.tracks.len()
+            ));
+            if ui.button("Copy as Text").clicked() {
+                ui.ctx().copy_text(animation.to_text());
+            }
+            if ui.button("Export Text").clicked() {
+                if let Some(file) = FileDialog::new()
+                    .add_filter("Text files", &["txt"])
+                    .set_file_name("annotations.txt")
+                    .save_file()
+                {
+                    self.status = Some(
+                        fs::write(&file, animation.to_text())
+                            .map(|()| format!("Exported {}", file.to_string_lossy()))
+                            .map_err(|e| format!("Failed to write {:?}: {}", file, e)),
+                    );
+                }
+            }
+            if ui.button("Import Text").clicked() {
+                if let Some(file) = FileDialog::new()
+                    .add_filter("Text files", &["txt"])
+                    .pick_file()
+                {
+                    self.status = Some(
+                        fs::read_to_string(&file)
+                            .map_err(anyhow::Error::from)
+                            .and_then(|text| animation.import_text(&text))
+                            .map(|()| format!("Imported {}", file.to_string_lossy()))
+                            .map_err(|e| format!("{:#}", e)),
+                    );
+                }
+            }
+        });
+    }
+
+    fn render_shift(&mut self, ui: &mut Ui) {
+        let Some(animation) = &mut self.animation else {
+            return;
+        };
+        ui.horizontal(|ui| {
+            ui.label("Shift annotations from");
+            ui.add(
+                egui::DragValue::new(&mut self.shift_from)
+                    .speed(0.01)
+                    .range(0.0..=animation.duration)
+                    .suffix("s"),
+            );
+            ui.label("by");
+            ui.add(
+                egui::DragValue::new(&mut self.shift_by)
+                    .speed(0.01)
+                    .suffix("s"),
+            );
+            if ui
+                .add_enabled(self.shift_by != 0.0, egui::Button::new("Shift"))
+                .clicked()
+            {
+                animation.shift(self.shift_from, self.shift_by);
+            }
+        });
+    }
+
+    fn render_tracks(&mut self, ui: &mut Ui) {
+        let Some(animation) = &mut self.animation else {
+            return;
+        };
+        let duration = animation.duration;
+        egui::ScrollArea::vertical()
+            .max_height(ui.available_height() - 60.0)
+            .auto_shrink([false, true])
+            .show(ui, |ui| {
+                for (track_index, track) in animation.tracks.iter_mut().enumerate() {
+                    egui::CollapsingHeader::new(format!(
+                        "{} ({})",
+                        track.name,
+                        track.annotations.len()
+                    ))
+                    .id_salt(("annotation_track", track_index))
+                    .default_open(!track.annotations.is_empty())
+                    .show(ui, |ui| {
+                        let mut to_remove = None;
+                        for (index, annotation) in track.annotations.iter_mut().enumerate() {
+                            ui.horizontal(|ui| {
+                                if ui.small_button("❌").clicked() {
+                                    to_remove = Some(index);
+                                }
+                                ui.add(
+                                    egui::DragValue::new(&mut annotation.time)
+                                        .speed(0.01)
+                                        .range(0.0..=duration)
+                                        .fixed_decimals(6),
+                                );
+                                ui.add(
+                                    egui::TextEdit::singleline(&mut annotation.text)
+                                        .desired_width(300.0),
+                                );
+                            });
+                        }
+                        if let Some(index) = to_remove {
+                            track.annotations.remove(index);
+                        }
+                        if ui.button("Add Annotation").clicked() {
+                            track.annotations.push(Annotation {
+                                time: 0.0,
+                                text: String::new(),
+                            });
+                        }
+                    });
+                }
+            });
+    }
+
+    fn render_save(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Save as:");
+            egui::ComboBox::from_id_salt("annotations_save_format")
+                .selected_text(self.save_format.label())
+                .show_ui(ui, |ui| {
+                    for format in SAVE_FORMATS {
+                        ui.selectable_value(&mut self.save_format, format, format.label());
+                    }
+                });
+            if self.save_format == OutputFormat::Kf {
+                ui.label("Skeleton:");
+                if let Some(skeleton) = &self.skeleton {
+                    ui.label(skeleton.file_name().unwrap_or_default().to_string_lossy());
+                }
+                if ui.button("Browse").clicked() {
+                    if let Some(file) = FileDialog::new()
+                        .add_filter("HKX files", &["hkx"])
+                        .pick_file()
+                    {
+                        self.skeleton = Some(file);
+                    }
+                }
+            }
+
+            if self.save_rx.is_some() {
+                ui.spinner();
+                return;
+            }
+            let ready = self.save_format != OutputFormat::Kf || self.skeleton.is_some();
+            if ui
+                .add_enabled(ready, egui::Button::new("Save As"))
+                .clicked()
+            {
+                self.save();
+            }
+        });
+    }
+
+    fn save(&mut self) {
+        let Some(animation) = &self.animation else {
+            return;
+        };
+        let file_name = self
+            .path
+            .as_ref()
+            .and_then(|path| path.file_stem())
+            .map(|stem| {
+                format!(
+                    "{}.{}",
+                    stem.to_string_lossy(),
+                    self.save_format.extension()
+                )
+            })
+            .unwrap_or_default();
+        let Some(output) = FileDialog::new().set_file_name(&file_name).save_file() else {
+            return;
+        };
+
+        // The animation stays editable while it is written, so save a copy
+        let document = animation.updated_document();
+        let (save_tx, save_rx) = oneshot::channel();
+        self.save_rx = Some(save_rx);
+        let format = self.save_format;
+        let hkxcmd_path = self.hkxcmd_path.clone();
+        let skeleton = self.skeleton.clone();
+
+        self.tokio_handle.spawn(async move {
+            let result = AnimationAnnotations::save_document(
+                &document,
+                &output,
+                format,
+                &hkxcmd_path,
+                skeleton.as_deref(),
+            )
+            .await
+            .map(|()| output);
+            let _ = save_tx.send(result);
+        });
+    }
+}
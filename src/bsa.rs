@@ -0,0 +1,714 @@
+//! Skyrim BSA archives: LE (version 104, zlib) and SE (version 105, LZ4 frames), read as
+//! conversion inputs and written to pack converted outputs.
+//!
+//! Entries are addressed by their path inside the archive, e.g.
+//! `meshes\actors\character\behaviors\0_master.hkx`. An input path that continues past a
+//! `.bsa` file (`...\Skyrim - Animations.bsa\meshes\...`) refers to an entry of that archive.
+
+use anyhow::{bail, Context as AnyhowContext, Result};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+const MAGIC: &[u8; 4] = b"BSA\0";
+const HEADER_SIZE: usize = 36;
+/// Hash, size and offset of one file
+const FILE_RECORD_SIZE: usize = 16;
+
+const ARCHIVE_INCLUDE_DIRECTORY_NAMES: u32 = 0x1;
+const ARCHIVE_INCLUDE_FILE_NAMES: u32 = 0x2;
+const ARCHIVE_COMPRESSED: u32 = 0x4;
+const ARCHIVE_EMBED_FILE_NAMES: u32 = 0x100;
+
+const FILE_FLAG_MESHES: u16 = 0x1;
+const FILE_FLAG_MISC: u16 = 0x100;
+
+/// Set in a file record's size when the file's compression differs from the archive default
+const SIZE_COMPRESSION_TOGGLE: u32 = 0x4000_0000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BsaVersion {
+    /// Skyrim LE, zlib compression
+    Le,
+    /// Skyrim SE, LZ4 frame compression
+    Se,
+}
+
+impl BsaVersion {
+    pub fn number(&self) -> u32 {
+        match self {
+            BsaVersion::Le => 104,
+            BsaVersion::Se => 105,
+        }
+    }
+
+    fn folder_record_size(&self) -> usize {
+        match self {
+            BsaVersion::Le => 16,
+            BsaVersion::Se => 24,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            BsaVersion::Le => "Skyrim LE (v104)",
+            BsaVersion::Se => "Skyrim SE (v105)",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BsaEntry {
+    /// Lowercase path inside the archive with `\` separators
+    pub path: String,
+    /// Size of the stored data, including an embedded name and the uncompressed size
+    pub size: u32,
+    pub offset: u32,
+    pub compressed: bool,
+}
+
+impl BsaEntry {
+    pub fn file_name(&self) -> &str {
+        self.path.rsplit('\\').next().unwrap_or(&self.path)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BsaArchive {
+    pub path: PathBuf,
+    pub version: BsaVersion,
+    flags: u32,
+    pub entries: Vec<BsaEntry>,
+}
+
+impl BsaArchive {
+    /// Read the folder and file records of an archive; file data is read on demand
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = File::open(path).with_context(|| format!("Failed to open {:?}", path))?;
+        let file_length = file.metadata()?.len();
+        let mut reader = BufReader::new(file);
+
+        let mut magic = [0; 4];
+        reader
+            .read_exact(&mut magic)
+            .context("File is too small for a BSA header")?;
+        if &magic != MAGIC {
+            bail!("Not a BSA archive: missing BSA magic");
+        }
+        let version = match read_u32(&mut reader)? {
+            104 => BsaVersion::Le,
+            105 => BsaVersion::Se,
+            other => bail!(
+                "Unsupported BSA version {} (only Skyrim LE 104 and SE 105)",
+                other
+            ),
+        };
+        let folder_offset = read_u32(&mut reader)?;
+        let flags = read_u32(&mut reader)?;
+        let folder_count = read_u32(&mut reader)?;
+        let file_count = read_u32(&mut reader)?;
+        let _total_folder_name_length = read_u32(&mut reader)?;
+        let _total_file_name_length = read_u32(&mut reader)?;
+        if flags & ARCHIVE_INCLUDE_DIRECTORY_NAMES == 0 || flags & ARCHIVE_INCLUDE_FILE_NAMES == 0 {
+            bail!("Archive does not store file names, so its entries can't be listed");
+        }
+        // The counts size the lists below, so a damaged header must not reserve gigabytes
+        let records_length = folder_count as u64 * version.folder_record_size() as u64
+            + file_count as u64 * FILE_RECORD_SIZE as u64;
+        if folder_offset as u64 + records_length > file_length {
+            bail!(
+                "Archive is truncated: {} folders and {} files don't fit in {} bytes",
+                folder_count,
+                file_count,
+                file_length
+            );
+        }
+
+        // Folder records: hash, file count, offset of the folder's file record block
+        reader.seek(SeekFrom::Start(folder_offset as u64))?;
+        let mut folder_file_counts = Vec::with_capacity(folder_count as usize);
+        for _ in 0..folder_count {
+            let _hash = read_u64(&mut reader)?;
+            folder_file_counts.push(read_u32(&mut reader)?);
+            match version {
+                BsaVersion::Le => {
+                    read_u32(&mut reader)?;
+                }
+                BsaVersion::Se => {
+                    read_u32(&mut reader)?;
+                    read_u64(&mut reader)?;
+                }
+            }
+        }
+
+        // File record blocks follow the folder records, each led by its folder's name
+        let mut folders = Vec::with_capacity(folder_count as usize);
+        for &count in &folder_file_counts {
+            let name = read_bzstring(&mut reader)?;
+            let mut records = Vec::new();
+            for _ in 0..count {
+                let _hash = read_u64(&mut reader)?;
+                let size = read_u32(&mut reader)?;
+                let offset = read_u32(&mut reader)?;
+                records.push((size, offset));
+            }
+            folders.push((name, records));
+        }
+
+        // Then the null-terminated file names, in record order
+        let mut entries = Vec::with_capacity(file_count as usize);
+        for (folder, records) in folders {
+            for (size, offset) in records {
+                let name = read_zstring(&mut reader)?;
+                let toggled = size & SIZE_COMPRESSION_TOGGLE != 0;
+                entries.push(BsaEntry {
+                    path: if folder.is_empty() {
+                        name
+                    } else {
+                        format!("{}\\{}", folder, name)
+                    },
+                    size: size & !SIZE_COMPRESSION_TOGGLE,
+                    offset,
+                    compressed: (flags & ARCHIVE_COMPRESSED != 0) != toggled,
+                });
+            }
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            version,
+            flags,
+            entries,
+        })
+    }
+
+    /// Find an entry by path, ignoring case and separator style
+    pub fn entry(&self, path: &str) -> Option<&BsaEntry> {
+        let path = normalize(path);
+        self.entries.iter().find(|entry| entry.path == path)
+    }
+
+    /// Read and decompress an entry's data
+    pub fn read(&self, entry: &BsaEntry) -> Result<Vec<u8>> {
+        let mut file =
+            File::open(&self.path).with_context(|| format!("Failed to open {:?}", self.path))?;
+        file.seek(SeekFrom::Start(entry.offset as u64))?;
+        let mut data = vec![0; entry.size as usize];
+        file.read_exact(&mut data)
+            .with_context(|| format!("Archive is truncated inside {}", entry.path))?;
+
+        let mut data = data.as_slice();
+        if self.flags & ARCHIVE_EMBED_FILE_NAMES != 0 {
+            let name_length = *data.first().context("Entry is empty")? as usize;
+            data = data
+                .get(1 + name_length..)
+                .context("Entry is too small for its embedded name")?;
+        }
+        if !entry.compressed {
+            return Ok(data.to_vec());
+        }
+
+        let original_size = data
+            .get(..4)
+            .context("Compressed entry is missing its size")?;
+        let original_size = u32::from_le_bytes(original_size.try_into().unwrap()) as usize;
+        let compressed = &data[4..];
+
+        let mut decompressed = Vec::with_capacity(original_size);
+        match self.version {
+            BsaVersion::Le => {
+                flate2::read::ZlibDecoder::new(compressed).read_to_end(&mut decompressed)
+            }
+            BsaVersion::Se => {
+                lz4_flex::frame::FrameDecoder::new(compressed).read_to_end(&mut decompressed)
+            }
+        }
+        .with_context(|| format!("Failed to decompress {}", entry.path))?;
+
+        if decompressed.len() != original_size {
+            bail!(
+                "{} decompressed to {} bytes, expected {}",
+                entry.path,
+                decompressed.len(),
+                original_size
+            );
+        }
+        Ok(decompressed)
+    }
+
+    /// Write an entry out as a normal file
+    pub fn extract(&self, entry: &BsaEntry, destination: &Path) -> Result<()> {
+        let data = self.read(entry)?;
+        fs::write(destination, data).with_context(|| format!("Failed to write {:?}", destination))
+    }
+
+    /// Input path referring to one of this archive's entries
+    pub fn entry_path(&self, entry: &BsaEntry) -> PathBuf {
+        entry
+            .path
+            .split('\\')
+            .fold(self.path.clone(), |path, component| path.join(component))
+    }
+}
+
+/// Extract an entry to a temporary folder so external tools can read it as a normal file.
+/// The file lives as long as the returned [`TempDir`].
+pub fn extract_to_temp(archive: &BsaArchive, entry_path: &str) -> Result<(PathBuf, TempDir)> {
+    let entry = archive
+        .entry(entry_path)
+        .with_context(|| format!("{} is not in {:?}", entry_path, archive.path))?;
+    let temp_dir = tempfile::Builder::new()
+        .prefix("bsa_entry_")
+        .tempdir()
+        .context("Failed to create temporary directory for archive entry")?;
+    let path = temp_dir.path().join(entry.file_name());
+    archive.extract(entry, &path)?;
+    Ok((path, temp_dir))
+}
+
+/// Split an input path that points into an archive into the archive and the entry path
+pub fn split_archive_path(path: &Path) -> Option<(PathBuf, String)> {
+    let archive = path.ancestors().skip(1).find(|ancestor| {
+        ancestor
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("bsa"))
+            && ancestor.is_file()
+    })?;
+    let entry = path.strip_prefix(archive).ok()?;
+    Some((archive.to_path_buf(), normalize(&entry.to_string_lossy())))
+}
+
+/// Where a converted batch is packed
+#[derive(Debug, Clone)]
+pub struct PackOptions {
+    pub archive: PathBuf,
+    pub version: BsaVersion,
+    pub compress: bool,
+}
+
+/// Archive folder for an input file: its folder inside the source archive, or the part of
+/// its path from `meshes` on. Files outside any `meshes` folder go to `meshes` itself.
+pub fn archive_folder_for(input: &Path) -> String {
+    if let Some((_, entry_path)) = split_archive_path(input) {
+        return entry_path
+            .rsplit_once('\\')
+            .map_or(String::new(), |(folder, _)| folder.to_string());
+    }
+
+    let components = input
+        .parent()
+        .map(|parent| {
+            parent
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    match components
+        .iter()
+        .rposition(|component| component.eq_ignore_ascii_case("meshes"))
+    {
+        Some(meshes) => normalize(&components[meshes..].join("\\")),
+        None => "meshes".to_string(),
+    }
+}
+
+/// Write a new archive from `(path inside the archive, file on disk)` pairs
+pub fn write_archive(
+    path: &Path,
+    version: BsaVersion,
+    compress: bool,
+    files: &[(String, PathBuf)],
+) -> Result<()> {
+    // Folders, and the files in each folder, are sorted by hash; the game relies on that
+    let mut folders: BTreeMap<u64, (String, BTreeMap<u64, PackedFile>)> = BTreeMap::new();
+    for (archive_path, source) in files {
+        let archive_path = normalize(archive_path);
+        let (folder, name) = archive_path
+            .rsplit_once('\\')
+            .unwrap_or(("", archive_path.as_str()));
+
+        let data = fs::read(source).with_context(|| format!("Failed to read {:?}", source))?;
+        let mut compressed = false;
+        let data = if compress {
+            let packed = compress_data(version, &data)?;
+            // Data that doesn't shrink is stored as is, marked with the compression toggle
+            if packed.len() < data.len() {
+                compressed = true;
+                packed
+            } else {
+                data
+            }
+        } else {
+            data
+        };
+
+        let (folder_name, folder_files) = folders
+            .entry(hash_folder(folder))
+            .or_insert_with(|| (folder.to_string(), BTreeMap::new()));
+        // Records are looked up by hash alone, so two paths with one hash can't both be stored
+        if *folder_name != folder {
+            bail!(
+                "Folders {:?} and {:?} have the same hash and can't be packed together",
+                folder_name,
+                folder
+            );
+        }
+        let packed = PackedFile {
+            name: name.to_string(),
+            data,
+            compressed,
+        };
+        if let Some(existing) = folder_files.insert(hash_file(name), packed) {
+            if existing.name == name {
+                bail!("{} is packed more than once", archive_path);
+            }
+            bail!(
+                "Files {:?} and {:?} in {:?} have the same hash and can't be packed together",
+                existing.name,
+                name,
+                folder
+            );
+        }
+    }
+
+    let folder_count = folders.len();
+    let packed_files = || folders.values().flat_map(|(_, files)| files.values());
+    let file_count = packed_files().count();
+    let total_folder_name_length = folders
+        .values()
+        .map(|(folder, _)| folder.len() + 1)
+        .sum::<usize>();
+    let total_file_name_length = packed_files()
+        .map(|file| file.name.len() + 1)
+        .sum::<usize>();
+
+    let file_records_start = HEADER_SIZE + folder_count * version.folder_record_size();
+    let file_names_start = file_records_start
+        + folder_count
+        + total_folder_name_length
+        + file_count * FILE_RECORD_SIZE;
+    let data_start = file_names_start + total_file_name_length;
+    let data_length = packed_files().map(|file| file.data.len()).sum::<usize>();
+    if data_start + data_length > u32::MAX as usize {
+        bail!("Archive would be larger than 4 GB");
+    }
+
+    let mut archive_flags = ARCHIVE_INCLUDE_DIRECTORY_NAMES | ARCHIVE_INCLUDE_FILE_NAMES;
+    if compress {
+        archive_flags |= ARCHIVE_COMPRESSED;
+    }
+    let only_meshes = folders
+        .values()
+        .all(|(folder, _)| folder == "meshes" || folder.starts_with("meshes\\"));
+    let file_flags = if only_meshes {
+        FILE_FLAG_MESHES
+    } else {
+        FILE_FLAG_MESHES | FILE_FLAG_MISC
+    };
+
+    let file = File::create(path).with_context(|| format!("Failed to create {:?}", path))?;
+    let mut out = BufWriter::new(file);
+
+    out.write_all(MAGIC)?;
+    for value in [
+        version.number(),
+        HEADER_SIZE as u32,
+        archive_flags,
+        folder_count as u32,
+        file_count as u32,
+        total_folder_name_length as u32,
+        total_file_name_length as u32,
+    ] {
+        out.write_all(&value.to_le_bytes())?;
+    }
+    out.write_all(&file_flags.to_le_bytes())?;
+    out.write_all(&[0; 2])?;
+
+    // Folder records point at their file record block, offset by the length of the file
+    // name block as the format requires
+    let mut block_offset = file_records_start;
+    for (&hash, (folder, files)) in &folders {
+        let offset = (block_offset + total_file_name_length) as u32;
+        out.write_all(&hash.to_le_bytes())?;
+        out.write_all(&(files.len() as u32).to_le_bytes())?;
+        match version {
+            BsaVersion::Le => out.write_all(&offset.to_le_bytes())?,
+            BsaVersion::Se => {
+                out.write_all(&[0; 4])?;
+                out.write_all(&(offset as u64).to_le_bytes())?;
+            }
+        }
+        block_offset += 1 + folder.len() + 1 + files.len() * FILE_RECORD_SIZE;
+    }
+
+    let mut data_offset = data_start;
+    for (folder, files) in folders.values() {
+        out.write_all(&[(folder.len() + 1) as u8])?;
+        out.write_all(&encode(folder)?)?;
+        out.write_all(&[0])?;
+        for (&hash, file) in files {
+            let mut size = file.data.len() as u32;
+            if file.compressed != compress {
+                size |= SIZE_COMPRESSION_TOGGLE;
+            }
+            out.write_all(&hash.to_le_bytes())?;
+            out.write_all(&size.to_le_bytes())?;
+            out.write_all(&(data_offset as u32).to_le_bytes())?;
+            data_offset += file.data.len();
+        }
+    }
+
+    for file in packed_files() {
+        out.write_all(&encode(&file.name)?)?;
+        out.write_all(&[0])?;
+    }
+    for file in packed_files() {
+        out.write_all(&file.data)?;
+    }
+
+    out.flush()
+        .with_context(|| format!("Failed to write {:?}", path))
+}
+
+struct PackedFile {
+    name: String,
+    data: Vec<u8>,
+    compressed: bool,
+}
+
+/// Compressed entry data: the uncompressed size, then a zlib stream (LE) or LZ4 frame (SE)
+fn compress_data(version: BsaVersion, data: &[u8]) -> Result<Vec<u8>> {
+    let mut out = (data.len() as u32).to_le_bytes().to_vec();
+    match version {
+        BsaVersion::Le => {
+            let mut encoder =
+                flate2::write::ZlibEncoder::new(&mut out, flate2::Compression::default());
+            encoder.write_all(data)?;
+            encoder.finish()?;
+        }
+        BsaVersion::Se => {
+            let mut encoder = lz4_flex::frame::FrameEncoder::new(&mut out);
+            encoder.write_all(data)?;
+            encoder.finish()?;
+        }
+    }
+    Ok(out)
+}
+
+fn hash_folder(folder: &str) -> u64 {
+    hash_name(folder.as_bytes(), b"")
+}
+
+fn hash_file(name: &str) -> u64 {
+    match name.rfind('.') {
+        Some(dot) => hash_name(&name.as_bytes()[..dot], &name.as_bytes()[dot..]),
+        None => hash_name(name.as_bytes(), b""),
+    }
+}
+
+/// The TES4 name hash: the first and last two characters and the length in the low half,
+/// rolling hashes of the middle characters and the extension in the high half
+fn hash_name(stem: &[u8], extension: &[u8]) -> u64 {
+    let length = stem.len();
+    let mut low = 0u32;
+    if length > 0 {
+        let second_to_last = if length > 2 {
+            stem[length - 2] as u32
+        } else {
+            0
+        };
+        low = stem[length - 1] as u32
+            | second_to_last << 8
+            | (length as u32) << 16
+            | (stem[0] as u32) << 24;
+    }
+    low |= match extension {
+        b".kf" => 0x80,
+        b".nif" => 0x8000,
+        b".dds" => 0x8080,
+        b".wav" => 0x8000_0000,
+        _ => 0,
+    };
+
+    let rolling = |bytes: &[u8]| {
+        bytes.iter().fold(0u32, |hash, &b| {
+            hash.wrapping_mul(0x1003F).wrapping_add(b as u32)
+        })
+    };
+    let middle = if length > 3 {
+        &stem[1..length - 2]
+    } else {
+        &[][..]
+    };
+    let high = rolling(middle).wrapping_add(rolling(extension));
+
+    ((high as u64) << 32) | low as u64
+}
+
+/// Lowercase with `\` separators, the way BSAs store paths
+pub fn normalize(path: &str) -> String {
+    path.replace('/', "\\")
+        .trim_matches('\\')
+        .to_ascii_lowercase()
+}
+
+fn read_u32(reader: &mut impl Read) -> Result<u32> {
+    let mut bytes = [0; 4];
+    reader
+        .read_exact(&mut bytes)
+        .context("Archive is truncated")?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64(reader: &mut impl Read) -> Result<u64> {
+    let mut bytes = [0; 8];
+    reader
+        .read_exact(&mut bytes)
+        .context("Archive is truncated")?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// Length-prefixed, null-terminated string
+fn read_bzstring(reader: &mut impl Read) -> Result<String> {
+    let mut length = [0; 1];
+    reader
+        .read_exact(&mut length)
+        .context("Archive is truncated")?;
+    let mut bytes = vec![0; length[0] as usize];
+    reader
+        .read_exact(&mut bytes)
+        .context("Archive is truncated")?;
+    Ok(decode(bytes.strip_suffix(&[0]).unwrap_or(&bytes)))
+}
+
+fn read_zstring(reader: &mut impl Read) -> Result<String> {
+    let mut bytes = Vec::new();
+    let mut byte = [0; 1];
+    loop {
+        reader
+            .read_exact(&mut byte)
+            .context("Archive is truncated")?;
+        if byte[0] == 0 {
+            return Ok(decode(&bytes));
+        }
+        bytes.push(byte[0]);
+    }
+}
+
+/// Names are stored in the Windows-1252 code page
+fn decode(bytes: &[u8]) -> String {
+    encoding_rs::WINDOWS_1252
+        .decode(bytes)
+        .0
+        .to_ascii_lowercase()
+}
+
+fn encode(name: &str) -> Result<Vec<u8>> {
+    let (bytes, _, had_errors) = encoding_rs::WINDOWS_1252.encode(name);
+    if had_errors || bytes.len() > 254 {
+        bail!("{:?} can't be stored as a BSA name", name);
+    }
+    Ok(bytes.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(version: BsaVersion, compress: bool) {
+        let dir = tempfile::tempdir().unwrap();
+        let behavior = dir.path().join("0_master.hkx");
+        let skeleton = dir.path().join("skeleton.hkx");
+        fs::write(&behavior, vec![7u8; 4096]).unwrap();
+        fs::write(&skeleton, b"hkx").unwrap();
+
+        let archive_path = dir.path().join("test.bsa");
+        write_archive(
+            &archive_path,
+            version,
+            compress,
+            &[
+                (
+                    "Meshes/Actors/Character/Behaviors/0_master.hkx".to_string(),
+                    behavior,
+                ),
+                (
+                    "meshes\\actors\\character\\character assets\\skeleton.hkx".to_string(),
+                    skeleton,
+                ),
+            ],
+        )
+        .unwrap();
+
+        let archive = BsaArchive::open(&archive_path).unwrap();
+        assert_eq!(archive.version, version);
+        assert_eq!(archive.entries.len(), 2);
+
+        let entry = archive
+            .entry("meshes\\actors\\character\\behaviors\\0_master.hkx")
+            .unwrap();
+        assert_eq!(entry.compressed, compress);
+        assert_eq!(archive.read(entry).unwrap(), vec![7u8; 4096]);
+
+        // Too small to shrink, so it is stored uncompressed even in a compressed archive
+        let entry = archive
+            .entry("meshes/actors/character/character assets/skeleton.hkx")
+            .unwrap();
+        assert!(!entry.compressed);
+        assert_eq!(archive.read(entry).unwrap(), b"hkx");
+    }
+
+    #[test]
+    fn round_trips_le_archives() {
+        round_trip(BsaVersion::Le, false);
+        round_trip(BsaVersion::Le, true);
+    }
+
+    #[test]
+    fn round_trips_se_archives() {
+        round_trip(BsaVersion::Se, false);
+        round_trip(BsaVersion::Se, true);
+    }
+
+    #[test]
+    fn rejects_duplicate_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("idle.kf");
+        fs::write(&source, b"kf").unwrap();
+
+        let result = write_archive(
+            &dir.path().join("test.bsa"),
+            BsaVersion::Se,
+            false,
+            &[
+                ("meshes\\idle.kf".to_string(), source.clone()),
+                ("Meshes/IDLE.kf".to_string(), source),
+            ],
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn rejects_counts_larger_than_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("broken.bsa");
+        let mut header = MAGIC.to_vec();
+        for field in [105, HEADER_SIZE as u32, 0x3, u32::MAX, u32::MAX, 0, 0, 0] {
+            header.extend_from_slice(&field.to_le_bytes());
+        }
+        fs::write(&path, header).unwrap();
+
+        assert!(BsaArchive::open(&path).is_err());
+    }
+
+    #[test]
+    fn hashes_names() {
+        assert_eq!(hash_folder("meshes"), 0x322F_3A9A_6D06_6573);
+        assert_eq!(hash_folder("ab"), 0x6102_0062);
+        assert_eq!(hash_file("idle.kf"), 0x1711_E44D_6904_6CE5);
+        assert_eq!(hash_file("0_master.hkx"), 0xC430_08DB_3008_6572);
+    }
+}
@@ -0,0 +1,133 @@
+//! Window for picking entries out of a BSA archive as conversion inputs.
+
+use crate::bsa::BsaArchive;
+use eframe::egui;
+use egui::{Color32, RichText};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+#[derive(Default)]
+pub struct BsaBrowser {
+    archive: Option<BsaArchive>,
+    error: Option<String>,
+    search: String,
+    /// Indices into `archive.entries`
+    selected: BTreeSet<usize>,
+    open: bool,
+}
+
+impl BsaBrowser {
+    pub fn open_archive(&mut self, path: &Path) {
+        self.open = true;
+        self.selected.clear();
+        self.search.clear();
+        match BsaArchive::open(path) {
+            Ok(archive) => {
+                self.archive = Some(archive);
+                self.error = None;
+            }
+            Err(e) => {
+                self.archive = None;
+                self.error = Some(format!("{:#}", e));
+            }
+        }
+    }
+
+    /// Draw the browser window. Returns the input paths of the entries the user added;
+    /// only entries accepted by `supports` are listed.
+    pub fn show(&mut self, ctx: &egui::Context, supports: impl Fn(&Path) -> bool) -> Vec<PathBuf> {
+        let mut added = Vec::new();
+        if !self.open {
+            return added;
+        }
+
+        let mut open = self.open;
+        egui::Window::new("BSA Archive")
+            .open(&mut open)
+            .default_size([600.0, 450.0])
+            .show(ctx, |ui| {
+                if let Some(error) = &self.error {
+                    ui.label(RichText::new(error).color(Color32::from_rgb(255, 120, 120)));
+                    return;
+                }
+                let Some(archive) = &self.archive else {
+                    return;
+                };
+
+                ui.label(format!(
+                    "{} - {}, {} files",
+                    archive
+                        .path
+                        .file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy(),
+                    archive.version.label(),
+                    archive.entries.len()
+                ));
+                ui.horizontal(|ui| {
+                    ui.label("Search:");
+                    ui.text_edit_singleline(&mut self.search);
+                });
+
+                let search = self.search.to_ascii_lowercase();
+                let shown = archive
+                    .entries
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, entry)| {
+                        entry.path.contains(&search) && supports(Path::new(&entry.path))
+                    })
+                    .map(|(index, _)| index)
+                    .collect::<Vec<_>>();
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .button(format!("Select All Shown ({})", shown.len()))
+                        .clicked()
+                    {
+                        self.selected.extend(shown.iter().copied());
+                    }
+                    if ui.button("Select None").clicked() {
+                        self.selected.clear();
+                    }
+                    if ui
+                        .add_enabled(
+                            !self.selected.is_empty(),
+                            egui::Button::new(format!("Add Selected ({})", self.selected.len())),
+                        )
+                        .clicked()
+                    {
+                        added.extend(
+                            self.selected
+                                .iter()
+                                .map(|&index| archive.entry_path(&archive.entries[index])),
+                        );
+                        self.selected.clear();
+                    }
+                });
+                ui.separator();
+
+                let row_height = ui.text_style_height(&egui::TextStyle::Body);
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false; 2])
+                    .show_rows(ui, row_height, shown.len(), |ui, rows| {
+                        for &index in &shown[rows] {
+                            let mut is_selected = self.selected.contains(&index);
+                            if ui
+                                .checkbox(&mut is_selected, &archive.entries[index].path)
+                                .changed()
+                            {
+                                if is_selected {
+                                    self.selected.insert(index);
+                                } else {
+                                    self.selected.remove(&index);
+                                }
+                            }
+                        }
+                    });
+            });
+        self.open = open;
+
+        added
+    }
+}
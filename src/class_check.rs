@@ -0,0 +1,236 @@
+//! Havok classes of an input and which converters can handle them, checked before converting:
+//! serde_hkx fails late on classes it doesn't know, and hkxcmd or hkxconv may drop them
+//! silently.
+
+use crate::hkx_document::HkxDocument;
+use crate::packfile::{PackfileHeader, SKYRIM_CONTENTS_VERSION};
+use crate::ConverterTool;
+use anyhow::Result;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassEntry {
+    pub name: String,
+    pub count: usize,
+    /// Layout hash identifying the class version; packfiles list it per class and XML
+    /// files per object
+    pub signature: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassReport {
+    pub contents_version: Option<String>,
+    /// 4 or 8 for binary packfiles, `None` for XML
+    pub pointer_size: Option<u8>,
+    pub classes: Vec<ClassEntry>,
+}
+
+impl ClassReport {
+    /// Classes of a binary packfile or Havok XML file
+    pub fn read(bytes: &[u8]) -> Result<Self> {
+        if let Ok(header) = PackfileHeader::parse(bytes) {
+            // Only the object counts are needed; a damaged class name table just loses the
+            // versions
+            let signatures = header.class_signatures(bytes).unwrap_or_default();
+            let classes = header
+                .object_classes(bytes)?
+                .into_iter()
+                .map(|(name, count)| ClassEntry {
+                    signature: signatures.get(&name).copied(),
+                    name,
+                    count,
+                })
+                .collect();
+            return Ok(Self {
+                contents_version: Some(header.contents_version),
+                pointer_size: Some(header.pointer_size),
+                classes,
+            });
+        }
+
+        let document = HkxDocument::from_xml(&String::from_utf8_lossy(bytes))?;
+        let mut classes: BTreeMap<&str, ClassEntry> = BTreeMap::new();
+        for object in &document.objects {
+            let Some(class) = &object.class else {
+                continue;
+            };
+            let entry = classes.entry(class).or_insert_with(|| ClassEntry {
+                name: class.clone(),
+                count: 0,
+                signature: object.signature.as_deref().and_then(|signature| {
+                    u32::from_str_radix(signature.trim_start_matches("0x"), 16).ok()
+                }),
+            });
+            entry.count += 1;
+        }
+        Ok(Self {
+            contents_version: document.contents_version.clone(),
+            pointer_size: None,
+            classes: classes.into_values().collect(),
+        })
+    }
+
+    pub fn class_names(&self) -> impl Iterator<Item = &str> {
+        self.classes.iter().map(|class| class.name.as_str())
+    }
+
+    /// Why `tool` can't convert this file; empty when it can
+    pub fn problems(&self, tool: ConverterTool) -> Vec<String> {
+        let mut problems = Vec::new();
+        if let Some(version) = &self.contents_version {
+            if version != SKYRIM_CONTENTS_VERSION {
+                problems.push(format!(
+                    "contents version {} is not Skyrim's {}",
+                    version, SKYRIM_CONTENTS_VERSION
+                ));
+            }
+        }
+        if tool == ConverterTool::HkxCmd && self.pointer_size == Some(8) {
+            problems.push("hkxcmd only reads 32-bit (LE) packfiles".to_string());
+        }
+        for class in &self.classes {
+            if let Some(problem) = class_problem(tool, class) {
+                problems.push(format!("{}: {}", class.name, problem));
+            }
+        }
+        problems
+    }
+}
+
+/// Layout signatures of the hk_2010.2.0-r1 classes Skyrim's files are made of, as Skyrim's
+/// Havok build and serde_hkx's class list define them. A class with another signature is a
+/// different version of it that none of the converters reads correctly.
+const SKYRIM_CLASS_SIGNATURES: &[(&str, u32)] = &[
+    ("hkRootLevelContainer", 0x2772c11e),
+    ("hkMemoryResourceContainer", 0x4762f92a),
+    ("hkaAnimationContainer", 0x8dc20333),
+    ("hkaAnimationBinding", 0x66eac971),
+    ("hkaDefaultAnimatedReferenceFrame", 0x6d85e445),
+    ("hkaInterleavedUncompressedAnimation", 0x930af031),
+    ("hkaRagdollInstance", 0x154948e8),
+    ("hkaSkeleton", 0x366e8220),
+    ("hkaSkeletonMapper", 0x12df42a5),
+    ("hkaSplineCompressedAnimation", 0x792ee0bb),
+    ("hkpBoxShape", 0x3444d2d5),
+    ("hkpCapsuleShape", 0xdd0b1fd6),
+    ("hkpPhysicsData", 0xc2a461e4),
+    ("hkpPhysicsSystem", 0xff724c17),
+    ("hkpRigidBody", 0x75f8d805),
+    ("hkbBehaviorGraph", 0xb1218f86),
+    ("hkbBehaviorGraphData", 0x095aca5d),
+    ("hkbBehaviorGraphStringData", 0xc713064e),
+    ("hkbBehaviorReferenceGenerator", 0x0fcb5423),
+    ("hkbBlenderGenerator", 0x22df7147),
+    ("hkbBlenderGeneratorChild", 0xe2b384b0),
+    ("hkbBlendingTransitionEffect", 0xfd8584fe),
+    ("hkbBoneWeightArray", 0xcd902b77),
+    ("hkbCharacterData", 0x300d6808),
+    ("hkbCharacterStringData", 0x655b42bc),
+    ("hkbClipGenerator", 0x333b85b9),
+    ("hkbClipTriggerArray", 0x59c23a0f),
+    ("hkbEvaluateExpressionModifier", 0xf900f6be),
+    ("hkbEventDrivenModifier", 0x7ed3f44e),
+    ("hkbExpressionCondition", 0x1c3c1045),
+    ("hkbExpressionDataArray", 0x4b9ee1a2),
+    ("hkbFootIkControlsModifier", 0xe5b6f544),
+    ("hkbFootIkDriverInfo", 0xc6a09dbf),
+    ("hkbKeyframeBonesModifier", 0x95f66629),
+    ("hkbManualSelectorGenerator", 0xd932fab8),
+    ("hkbMirroredSkeletonInfo", 0xc6c2da4f),
+    ("hkbModifierGenerator", 0x1f81fae6),
+    ("hkbModifierList", 0xa4180ca1),
+    ("hkbPoseMatchingGenerator", 0x29e271b4),
+    ("hkbPoweredRagdollControlsModifier", 0x7cb54065),
+    ("hkbProjectData", 0x13a39ba7),
+    ("hkbProjectStringData", 0x076ad60a),
+    ("hkbStateMachine", 0x816c1dcb),
+    ("hkbStateMachineEventPropertyArray", 0xb07b4388),
+    ("hkbStateMachineStateInfo", 0x0ed7f9d0),
+    ("hkbStateMachineTransitionInfoArray", 0xe397b11e),
+    ("hkbStringCondition", 0x5ab50487),
+    ("hkbStringEventPayload", 0xed04256a),
+    ("hkbTimerModifier", 0x338b4879),
+    ("hkbVariableBindingSet", 0x338ad4ff),
+    ("hkbVariableValueSet", 0x27812d8d),
+    ("BSBoneSwitchGenerator", 0xf33d3eea),
+    ("BSBoneSwitchGeneratorBoneData", 0xc1215be6),
+    ("BSComputeAddBoneAnimModifier", 0xa67f8c46),
+    ("BSCyclicBlendTransitionGenerator", 0x5119eb06),
+    ("BSDecomposeVectorModifier", 0x31f6b8b6),
+    ("BSDirectAtModifier", 0x19a005c0),
+    ("BSDistTriggerModifier", 0xb34d2bbd),
+    ("BSEventEveryNEventsModifier", 0x6030970c),
+    ("BSEventOnDeactivateModifier", 0x1062d993),
+    ("BSEventOnFalseToTrueModifier", 0x81d0777a),
+    ("BSGetTimeStepModifier", 0xbda33bfe),
+    ("BSInterpValueModifier", 0x29adc802),
+    ("BSIsActiveModifier", 0xb0fde45a),
+    ("BSLimbIKModifier", 0x8ea971e5),
+    ("BSLookAtModifier", 0xd756fc25),
+    ("BSModifyOnceModifier", 0x1e20a97a),
+    ("BSOffsetAnimationGenerator", 0xb8571122),
+    ("BSPassByTargetTriggerModifier", 0x703d7b66),
+    ("BSRagdollContactListenerModifier", 0x8003d8ce),
+    ("BSSpeedSamplerModifier", 0xd297fda9),
+    ("BSSynchronizedClipGenerator", 0xd83bea64),
+    ("BSTimerModifier", 0x531f3292),
+    ("BSTweenerModifier", 0x0d2d9a04),
+    ("BSiStateTaggingGenerator", 0xf0826fc1),
+];
+
+/// Why `tool` can't handle `class`. Listed classes are checked by signature; others are
+/// judged by class family: stock Havok classes (`hk*`, `hcl*`) and Bethesda's (`BS*`) are
+/// the ones Skyrim's Havok build registers.
+fn class_problem(tool: ConverterTool, class: &ClassEntry) -> Option<String> {
+    let expected = SKYRIM_CLASS_SIGNATURES
+        .iter()
+        .find(|(name, _)| *name == class.name)
+        .map(|&(_, signature)| signature);
+    match (expected, class.signature) {
+        (Some(expected), Some(signature)) if signature != expected => {
+            return Some(format!(
+                "signature 0x{:08x} is not the Skyrim layout 0x{:08x}; {}",
+                signature,
+                expected,
+                match tool {
+                    ConverterTool::HkxC => "serde_hkx can't read this version",
+                    _ => "this version is read wrongly or dropped",
+                }
+            ));
+        }
+        (Some(_), _) => {}
+        (None, _) => {
+            let havok = class.name.starts_with("hk") || class.name.starts_with("hcl");
+            let bethesda = class.name.starts_with("BS");
+            if !havok && !bethesda {
+                return Some(
+                    match tool {
+                        ConverterTool::HkxC => {
+                            "not a Havok 2010 or Bethesda class; serde_hkx can't read it"
+                        }
+                        ConverterTool::HkxCmd | ConverterTool::HkxConv => {
+                            "not a Havok 2010 or Bethesda class; it may be dropped from the output"
+                        }
+                        ConverterTool::Hct | ConverterTool::HavokBehaviorPostProcess => {
+                            "not a Havok 2010 or Bethesda class"
+                        }
+                    }
+                    .to_string(),
+                );
+            }
+        }
+    }
+
+    let problem = match tool {
+        ConverterTool::Hct if class.name.starts_with("BS") => {
+            "Bethesda classes are unknown to the stock HCT"
+        }
+        ConverterTool::HavokBehaviorPostProcess
+            if class.name.starts_with("hka") || class.name.starts_with("hkp") =>
+        {
+            "HavokBehaviorPostProcess only converts behavior graphs"
+        }
+        _ => return None,
+    };
+    Some(problem.to_string())
+}
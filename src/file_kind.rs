@@ -0,0 +1,202 @@
+//! What an input file holds, judged by the classes of its objects: binary packfiles list
+//! them in their class fixups and class name table, so only XML files need parsing.
+
+use crate::bsa::{self, BsaArchive};
+use crate::class_check::ClassReport;
+use crate::ConverterTool;
+use anyhow::{Context as AnyhowContext, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::sync::oneshot;
+
+/// KF files written by hkxcmd are Gamebryo NIF streams
+const KF_MAGIC: &[u8] = b"Gamebryo File Format";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FileKind {
+    Behavior,
+    Animation,
+    Skeleton,
+    Project,
+    Character,
+    Other,
+}
+
+impl FileKind {
+    pub const ALL: [Self; 6] = [
+        Self::Behavior,
+        Self::Animation,
+        Self::Skeleton,
+        Self::Project,
+        Self::Character,
+        Self::Other,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Behavior => "behavior",
+            Self::Animation => "animation",
+            Self::Skeleton => "skeleton",
+            Self::Project => "project",
+            Self::Character => "character",
+            Self::Other => "other",
+        }
+    }
+
+    /// Judge a file by the classes of its objects
+    pub fn from_classes<'a>(classes: impl IntoIterator<Item = &'a str>) -> Self {
+        let classes = classes.into_iter().collect::<Vec<_>>();
+        let has = |class: &str| classes.contains(&class);
+        if has("hkbProjectData") {
+            Self::Project
+        } else if has("hkbCharacterData") {
+            Self::Character
+        } else if has("hkbBehaviorGraph") {
+            Self::Behavior
+        } else if classes.iter().any(|class| is_animation_class(class)) {
+            Self::Animation
+        } else if has("hkaSkeleton") {
+            Self::Skeleton
+        } else {
+            Self::Other
+        }
+    }
+}
+
+/// `hkaSplineCompressedAnimation` and the other `hkaAnimation` subclasses
+fn is_animation_class(class: &str) -> bool {
+    class.starts_with("hka")
+        && class.ends_with("Animation")
+        && class != "hkaAnimation"
+        && class != "hkaMirroredAnimation"
+}
+
+/// What [`KindDetector`] learns about an input
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputInfo {
+    pub kind: FileKind,
+    /// `LE`, `SE`, `XML` or `KF`
+    pub format: &'static str,
+    pub size: u64,
+    /// `None` for KF files, which hold no Havok objects
+    pub classes: Option<ClassReport>,
+}
+
+fn detect(bytes: &[u8]) -> Result<InputInfo> {
+    if bytes.starts_with(KF_MAGIC) {
+        return Ok(InputInfo {
+            kind: FileKind::Animation,
+            format: "KF",
+            size: bytes.len() as u64,
+            classes: None,
+        });
+    }
+    let classes = ClassReport::read(bytes)?;
+    Ok(InputInfo {
+        kind: FileKind::from_classes(classes.class_names()),
+        format: match classes.pointer_size {
+            Some(4) => "LE",
+            Some(8) => "SE",
+            Some(_) => "?",
+            None => "XML",
+        },
+        size: bytes.len() as u64,
+        classes: Some(classes),
+    })
+}
+
+/// Contents of an input path, which may be an entry of a BSA archive
+pub fn read_input(
+    path: &Path,
+    archives: &Mutex<HashMap<PathBuf, Arc<BsaArchive>>>,
+) -> Result<Vec<u8>> {
+    let Some((archive_path, entry_path)) = bsa::split_archive_path(path) else {
+        return fs::read(path).with_context(|| format!("Failed to read {:?}", path));
+    };
+
+    let archive = {
+        let mut archives = archives.lock().unwrap();
+        match archives.get(&archive_path) {
+            Some(archive) => archive.clone(),
+            None => {
+                let archive = Arc::new(BsaArchive::open(&archive_path)?);
+                archives.insert(archive_path, archive.clone());
+                archive
+            }
+        }
+    };
+    let entry = archive
+        .entry(&entry_path)
+        .with_context(|| format!("{} is not in the archive", entry_path))?;
+    archive.read(entry)
+}
+
+enum KindCheck {
+    Detecting(oneshot::Receiver<Result<InputInfo>>),
+    Done(std::result::Result<InputInfo, String>),
+}
+
+/// Detects input kinds and classes in the background so the file list can show them
+pub struct KindDetector {
+    checks: HashMap<PathBuf, KindCheck>,
+    /// Archives stay open so entries of the same archive don't re-read its directory
+    archives: Arc<Mutex<HashMap<PathBuf, Arc<BsaArchive>>>>,
+    tokio_handle: tokio::runtime::Handle,
+}
+
+impl KindDetector {
+    pub fn new(tokio_handle: tokio::runtime::Handle) -> Self {
+        Self {
+            checks: HashMap::new(),
+            archives: Arc::new(Mutex::new(HashMap::new())),
+            tokio_handle,
+        }
+    }
+
+    /// What `path` holds, or `None` while it is still being read
+    pub fn info(&mut self, path: &Path) -> Option<&std::result::Result<InputInfo, String>> {
+        let check = self.checks.entry(path.to_path_buf()).or_insert_with(|| {
+            let (tx, rx) = oneshot::channel();
+            let path = path.to_path_buf();
+            let archives = self.archives.clone();
+            // Reading and parsing block, so they stay off the async worker threads
+            self.tokio_handle.spawn_blocking(move || {
+                let _ = tx.send(read_input(&path, &archives).and_then(|bytes| detect(&bytes)));
+            });
+            KindCheck::Detecting(rx)
+        });
+
+        if let KindCheck::Detecting(rx) = check {
+            match rx.try_recv() {
+                Ok(result) => *check = KindCheck::Done(result.map_err(|e| format!("{:#}", e))),
+                Err(oneshot::error::TryRecvError::Empty) => return None,
+                Err(oneshot::error::TryRecvError::Closed) => {
+                    *check = KindCheck::Done(Err("Reading the file was interrupted".to_string()))
+                }
+            }
+        }
+
+        match check {
+            KindCheck::Done(result) => Some(result),
+            KindCheck::Detecting(_) => None,
+        }
+    }
+
+    /// The detected kind, if detection has finished and succeeded
+    pub fn known_kind(&mut self, path: &Path) -> Option<FileKind> {
+        Some(self.info(path)?.as_ref().ok()?.kind)
+    }
+
+    /// Why `tool` can't convert `path`; empty when it can or the file is still being read
+    pub fn problems(&mut self, path: &Path, tool: ConverterTool) -> Vec<String> {
+        match self.info(path) {
+            Some(Ok(InputInfo {
+                classes: Some(classes),
+                ..
+            })) => classes.problems(tool),
+            _ => Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,252 @@
+//! Rows of the converter's file table: what each input is and how its conversion went.
+
+use crate::file_kind::{FileKind, KindDetector};
+use crate::tool_run::ToolRun;
+use eframe::egui::Color32;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FileState {
+    Queued,
+    Running,
+    Ok,
+    Failed,
+    /// The run ended (cancelled or stopped by an error) before the file was converted
+    Skipped,
+    /// Left out of the run because its converter can't handle the file's classes
+    Unavailable,
+}
+
+impl FileState {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Queued => "queued",
+            Self::Running => "running",
+            Self::Ok => "ok",
+            Self::Failed => "failed",
+            Self::Skipped => "skipped",
+            Self::Unavailable => "unavailable",
+        }
+    }
+
+    pub fn color(&self) -> Color32 {
+        match self {
+            Self::Queued | Self::Skipped => Color32::from_rgb(100, 100, 100),
+            Self::Running => Color32::from_rgb(100, 150, 255),
+            Self::Ok => Color32::from_rgb(100, 200, 100),
+            Self::Failed => Color32::from_rgb(255, 120, 120),
+            Self::Unavailable => Color32::from_rgb(255, 180, 80),
+        }
+    }
+}
+
+/// Progress of one file, sent by the conversion task
+#[derive(Debug, Clone)]
+pub struct FileUpdate {
+    pub input: PathBuf,
+    pub state: FileState,
+    /// Where the output goes; an archive path followed by its entry when packing
+    pub output: Option<PathBuf>,
+    /// Time spent converting, once the file is done
+    pub duration: Option<Duration>,
+    pub error: Option<String>,
+    /// The converter's command and output, when it was the converter that failed
+    pub run: Option<ToolRun>,
+    /// HCT's working folder with its log and `.hko`, kept when the file failed
+    pub kept_temp_dir: Option<PathBuf>,
+}
+
+impl FileUpdate {
+    pub fn queued(input: PathBuf) -> Self {
+        Self {
+            input,
+            state: FileState::Queued,
+            output: None,
+            duration: None,
+            error: None,
+            run: None,
+            kept_temp_dir: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileColumn {
+    Name,
+    Folder,
+    Size,
+    Format,
+    Kind,
+    Status,
+    Duration,
+    Output,
+}
+
+impl FileColumn {
+    pub const ALL: [Self; 8] = [
+        Self::Name,
+        Self::Folder,
+        Self::Size,
+        Self::Format,
+        Self::Kind,
+        Self::Status,
+        Self::Duration,
+        Self::Output,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Name => "Name",
+            Self::Folder => "Folder",
+            Self::Size => "Size",
+            Self::Format => "Format",
+            Self::Kind => "Kind",
+            Self::Status => "Status",
+            Self::Duration => "Duration",
+            Self::Output => "Output",
+        }
+    }
+
+    pub fn compare(&self, a: &FileRow, b: &FileRow) -> Ordering {
+        match self {
+            Self::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            Self::Folder => a.folder.to_lowercase().cmp(&b.folder.to_lowercase()),
+            Self::Size => a.size.cmp(&b.size),
+            Self::Format => a.format.cmp(&b.format),
+            Self::Kind => a.kind.cmp(&b.kind),
+            Self::Status => a.state.cmp(&b.state),
+            Self::Duration => a.duration.cmp(&b.duration),
+            Self::Output => a.output.cmp(&b.output),
+        }
+    }
+}
+
+/// One input as the table shows it
+#[derive(Debug, Clone)]
+pub struct FileRow {
+    /// Index into the input list
+    pub index: usize,
+    pub name: String,
+    /// Folder below the inputs' common folder
+    pub folder: String,
+    pub size: Option<u64>,
+    pub format: Option<&'static str>,
+    pub kind: Option<FileKind>,
+    /// Why the file couldn't be read; `None` while it is still being read
+    pub error: Option<String>,
+    pub state: Option<FileState>,
+    pub duration: Option<Duration>,
+    pub output: Option<PathBuf>,
+}
+
+/// Rows for `input_paths` in `sort` order, with paths relative to `common_folder`
+pub fn rows(
+    input_paths: &[PathBuf],
+    common_folder: Option<&Path>,
+    detector: &mut KindDetector,
+    updates: &HashMap<PathBuf, FileUpdate>,
+    sort: Option<(FileColumn, bool)>,
+) -> Vec<FileRow> {
+    let mut rows = input_paths
+        .iter()
+        .enumerate()
+        .map(|(index, path)| {
+            let detected = detector.info(path);
+            let info = detected.and_then(|info| info.as_ref().ok());
+            let update = updates.get(path);
+            let relative = common_folder
+                .and_then(|folder| path.strip_prefix(folder).ok())
+                .unwrap_or(path);
+            FileRow {
+                index,
+                name: path
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string(),
+                folder: relative
+                    .parent()
+                    .unwrap_or(Path::new(""))
+                    .to_string_lossy()
+                    .to_string(),
+                size: info.map(|info| info.size),
+                format: info.map(|info| info.format),
+                kind: info.map(|info| info.kind),
+                error: detected.and_then(|info| info.as_ref().err().cloned()),
+                state: update.map(|update| update.state),
+                duration: update.and_then(|update| update.duration),
+                output: update.and_then(|update| update.output.clone()),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if let Some((column, ascending)) = sort {
+        rows.sort_by(|a, b| column.compare(a, b));
+        if !ascending {
+            rows.reverse();
+        }
+    }
+    rows
+}
+
+impl FileRow {
+    /// Whether the row's folder or name contains `search`, which must be lowercase
+    pub fn matches_search(&self, search: &str) -> bool {
+        search.is_empty()
+            || self.name.to_lowercase().contains(search)
+            || self.folder.to_lowercase().contains(search)
+    }
+}
+
+/// A line of the file table: a file, or the header of a folder when grouping
+#[derive(Debug, Clone)]
+pub enum TableLine {
+    Folder {
+        folder: String,
+        files: usize,
+        collapsed: bool,
+    },
+    File(FileRow),
+}
+
+/// Rows under a header per folder, keeping their order within each folder. Files of
+/// `collapsed` folders are left out.
+pub fn group_by_folder(mut rows: Vec<FileRow>, collapsed: &HashSet<String>) -> Vec<TableLine> {
+    rows.sort_by(|a, b| FileColumn::Folder.compare(a, b));
+    let mut lines = Vec::new();
+    let mut rows = rows.into_iter().peekable();
+    while let Some(first) = rows.next() {
+        let folder = first.folder.clone();
+        let mut files = vec![first];
+        while let Some(row) = rows.next_if(|row| row.folder.eq_ignore_ascii_case(&folder)) {
+            files.push(row);
+        }
+        let is_collapsed = collapsed.contains(&folder);
+        lines.push(TableLine::Folder {
+            folder,
+            files: files.len(),
+            collapsed: is_collapsed,
+        });
+        if !is_collapsed {
+            lines.extend(files.into_iter().map(TableLine::File));
+        }
+    }
+    lines
+}
+
+pub fn format_size(bytes: u64) -> String {
+    if bytes < 1024 {
+        format!("{} B", bytes)
+    } else if bytes < 1024 * 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    }
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    format!("{:.2}s", duration.as_secs_f32())
+}
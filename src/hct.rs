@@ -0,0 +1,865 @@
+//! HCT (Havok Content Tools) option files.
+//!
+//! `hctStandAloneFilterManager.exe` is driven by `.hko` files: a configuration set holding one
+//! or more configurations, each a chain of filters with their options. This module models the
+//! filters we use and writes them back out in the layout the filter manager expects.
+
+use crate::tool_run::ToolRun;
+use crate::xml::{self, XmlElement};
+use crate::OutputFormat;
+use anyhow::{bail, Context as AnyhowContext, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const FILTER_MANAGER_VERSION: u32 = 65536;
+pub const FILTER_MANAGER_EXE: &str = "hctStandAloneFilterManager.exe";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HkoOptions {
+    pub filter_manager_version: u32,
+    pub active_configuration: usize,
+    pub configurations: Vec<HctConfiguration>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HctConfiguration {
+    pub name: String,
+    pub filters: Vec<HctFilter>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HctFilter {
+    pub name: String,
+    pub id: u32,
+    pub version: u32,
+    pub options: Option<FilterOptions>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterOptions {
+    MergeAsset(MergeAssetOptions),
+    PruneTypes(PruneTypesOptions),
+    PlatformWriter(PlatformWriterOptions),
+    /// Options of filters we don't model (or can't represent), kept verbatim
+    Raw(XmlElement),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeAssetOptions {
+    pub filename: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PruneTypesOptions {
+    pub prune_scene_data: bool,
+    pub prune_mesh_data: bool,
+    pub prune_skeleton_data: bool,
+    pub prune_animation_data: bool,
+    pub custom_classes: String,
+    pub prune_all_scene_data: bool,
+    pub prune_environment_data: bool,
+    pub prune_resource_data: bool,
+    pub prune_destruction_data: bool,
+    pub prune_animation_tracks: bool,
+    pub prune_annotations: bool,
+    pub prune_identity_binding_indices: bool,
+    pub prune_quantized_bindings: bool,
+    pub prune_attributes: bool,
+    pub prune_mesh_user_channels: bool,
+    pub prune_attachments: bool,
+    pub prune_selection_sets: bool,
+    pub selection_sets: String,
+    pub selection_deletion_mode: String,
+    pub prune_all_animation_data: bool,
+    pub prune_mesh_binding_data: bool,
+    pub prune_ragdoll_and_mapper_data: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlatformWriterOptions {
+    pub filename: String,
+    pub tagfile: bool,
+    pub preset: PlatformPreset,
+    pub bytes_in_pointer: u8,
+    pub little_endian: bool,
+    pub reuse_padding_optimized: bool,
+    pub empty_base_class_optimized: bool,
+    pub remove_metadata: bool,
+    pub user_tag: u32,
+    pub save_environment_data: bool,
+    pub xml_format: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlatformPreset {
+    MsvcWin32,
+    MsvcAmd64,
+    Custom,
+}
+
+impl PlatformPreset {
+    pub const ALL: [PlatformPreset; 3] = [
+        PlatformPreset::MsvcWin32,
+        PlatformPreset::MsvcAmd64,
+        PlatformPreset::Custom,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PlatformPreset::MsvcWin32 => "MSVC_WIN32",
+            PlatformPreset::MsvcAmd64 => "MSVC_AMD64",
+            PlatformPreset::Custom => "CUSTOM",
+        }
+    }
+
+    pub fn parse(text: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|preset| preset.as_str() == text)
+    }
+
+    /// Pointer size the preset implies, `None` for a custom layout
+    pub fn bytes_in_pointer(&self) -> Option<u8> {
+        match self {
+            PlatformPreset::MsvcWin32 => Some(4),
+            PlatformPreset::MsvcAmd64 => Some(8),
+            PlatformPreset::Custom => None,
+        }
+    }
+}
+
+impl HctFilter {
+    pub const MERGE_ASSET: &'static str = "Merge Asset";
+    pub const PRUNE_TYPES: &'static str = "Prune Types";
+    pub const WRITE_TO_PLATFORM: &'static str = "Write to Platform";
+
+    pub fn merge_asset(options: MergeAssetOptions) -> Self {
+        Self {
+            name: Self::MERGE_ASSET.to_string(),
+            id: 3033372433,
+            version: 65536,
+            options: Some(FilterOptions::MergeAsset(options)),
+        }
+    }
+
+    pub fn prune_types(options: PruneTypesOptions) -> Self {
+        Self {
+            name: Self::PRUNE_TYPES.to_string(),
+            id: 2496164476,
+            version: 66048,
+            options: Some(FilterOptions::PruneTypes(options)),
+        }
+    }
+
+    pub fn write_to_platform(options: PlatformWriterOptions) -> Self {
+        Self {
+            name: Self::WRITE_TO_PLATFORM.to_string(),
+            id: 2876798309,
+            version: 66048,
+            options: Some(FilterOptions::PlatformWriter(options)),
+        }
+    }
+}
+
+impl Default for MergeAssetOptions {
+    fn default() -> Self {
+        Self {
+            filename: "Replace".to_string(),
+        }
+    }
+}
+
+impl Default for PruneTypesOptions {
+    /// Flags used for SSE -> LE animation conversion
+    fn default() -> Self {
+        Self {
+            prune_scene_data: true,
+            prune_mesh_data: false,
+            prune_skeleton_data: true,
+            prune_animation_data: false,
+            custom_classes: String::new(),
+            prune_all_scene_data: true,
+            prune_environment_data: true,
+            prune_resource_data: true,
+            prune_destruction_data: false,
+            prune_animation_tracks: false,
+            prune_annotations: false,
+            prune_identity_binding_indices: true,
+            prune_quantized_bindings: true,
+            prune_attributes: false,
+            prune_mesh_user_channels: false,
+            prune_attachments: true,
+            prune_selection_sets: false,
+            selection_sets: String::new(),
+            selection_deletion_mode: "HK_SELECTION_DELETE_SELECTED".to_string(),
+            prune_all_animation_data: false,
+            prune_mesh_binding_data: true,
+            prune_ragdoll_and_mapper_data: true,
+        }
+    }
+}
+
+impl PruneTypesOptions {
+    /// Boolean flags with their `.hko` names, for building checkbox lists
+    pub fn flags_mut(&mut self) -> [(&'static str, &mut bool); 19] {
+        [
+            ("pruneSceneData", &mut self.prune_scene_data),
+            ("pruneMeshData", &mut self.prune_mesh_data),
+            ("pruneSkeletonData", &mut self.prune_skeleton_data),
+            ("pruneAnimationData", &mut self.prune_animation_data),
+            ("pruneAllSceneData", &mut self.prune_all_scene_data),
+            ("pruneEnvironmentData", &mut self.prune_environment_data),
+            ("pruneResourceData", &mut self.prune_resource_data),
+            ("pruneDestructionData", &mut self.prune_destruction_data),
+            ("pruneAnimationTracks", &mut self.prune_animation_tracks),
+            ("pruneAnnotations", &mut self.prune_annotations),
+            (
+                "pruneIdentityBindingIndices",
+                &mut self.prune_identity_binding_indices,
+            ),
+            ("pruneQuantizedBindings", &mut self.prune_quantized_bindings),
+            ("pruneAttributes", &mut self.prune_attributes),
+            ("pruneMeshUserChannels", &mut self.prune_mesh_user_channels),
+            ("pruneAttachments", &mut self.prune_attachments),
+            ("pruneSelectionSets", &mut self.prune_selection_sets),
+            ("pruneAllAnimationData", &mut self.prune_all_animation_data),
+            ("pruneMeshBindingData", &mut self.prune_mesh_binding_data),
+            (
+                "pruneRagdollAndMapperData",
+                &mut self.prune_ragdoll_and_mapper_data,
+            ),
+        ]
+    }
+}
+
+impl Default for PlatformWriterOptions {
+    /// 32-bit packfile as Skyrim LE expects
+    fn default() -> Self {
+        Self {
+            filename: "filename.hkx".to_string(),
+            tagfile: false,
+            preset: PlatformPreset::MsvcWin32,
+            bytes_in_pointer: 4,
+            little_endian: true,
+            reuse_padding_optimized: false,
+            empty_base_class_optimized: true,
+            remove_metadata: true,
+            user_tag: 0,
+            save_environment_data: false,
+            xml_format: false,
+        }
+    }
+}
+
+impl PlatformWriterOptions {
+    /// Switch the writer to the preset, pointer size and format that produce `format`
+    pub fn set_output_format(&mut self, format: OutputFormat) {
+        match format {
+            OutputFormat::SkyrimLE => {
+                self.preset = PlatformPreset::MsvcWin32;
+                self.bytes_in_pointer = 4;
+                self.xml_format = false;
+            }
+            OutputFormat::SkyrimSE => {
+                self.preset = PlatformPreset::MsvcAmd64;
+                self.bytes_in_pointer = 8;
+                self.xml_format = false;
+            }
+            OutputFormat::Xml => self.xml_format = true,
+            OutputFormat::Kf => return,
+        }
+        self.tagfile = false;
+        self.little_endian = true;
+        self.reuse_padding_optimized = false;
+        self.empty_base_class_optimized = true;
+        self.filename = format!("filename.{}", format.extension());
+    }
+
+    /// Format these options write, if it is one Skyrim reads
+    pub fn output_format(&self) -> Option<OutputFormat> {
+        format_of(self.xml_format, self.bytes_in_pointer)
+    }
+}
+
+fn format_of(xml_format: bool, bytes_in_pointer: u8) -> Option<OutputFormat> {
+    match (xml_format, bytes_in_pointer) {
+        (true, _) => Some(OutputFormat::Xml),
+        (false, 4) => Some(OutputFormat::SkyrimLE),
+        (false, 8) => Some(OutputFormat::SkyrimSE),
+        _ => None,
+    }
+}
+
+impl HkoOptions {
+    pub fn load(path: &Path) -> Result<Self> {
+        let bytes = fs::read(path).with_context(|| format!("Failed to read {:?}", path))?;
+        Self::parse(&String::from_utf8_lossy(&bytes))
+            .with_context(|| format!("Failed to parse {:?}", path))
+    }
+
+    /// Parse a `.hko` file. Objects come in file order: the configuration set, then each
+    /// configuration followed by its filters, each filter followed by its options.
+    pub fn parse(text: &str) -> Result<Self> {
+        let root = xml::parse(text)?;
+        if root.name != "hkoptions" {
+            bail!("Not an HCT option file: root element is <{}>", root.name);
+        }
+
+        let mut options = Self {
+            filter_manager_version: FILTER_MANAGER_VERSION,
+            active_configuration: 0,
+            configurations: Vec::new(),
+        };
+
+        for object in root.elements().filter(|e| e.name == "hkobject") {
+            let params = Params::of(object);
+            match object.attribute("class").unwrap_or_default() {
+                "hctConfigurationSetData" => {
+                    options.filter_manager_version =
+                        params.number("filterManagerVersion", FILTER_MANAGER_VERSION);
+                    options.active_configuration = params.number("activeConfiguration", 0);
+                }
+                "hctConfigurationData" => options.configurations.push(HctConfiguration {
+                    name: params.string("configurationName", ""),
+                    filters: Vec::new(),
+                }),
+                "hctFilterData" => {
+                    let Some(configuration) = options.configurations.last_mut() else {
+                        bail!("Filter found before any configuration");
+                    };
+                    configuration.filters.push(HctFilter {
+                        name: object.attribute("name").unwrap_or_default().to_string(),
+                        id: params.number("id", 0),
+                        version: params.number("ver", 0),
+                        options: None,
+                    });
+                }
+                _ => {
+                    let name = object.attribute("name").unwrap_or_default();
+                    let filter = options
+                        .configurations
+                        .last_mut()
+                        .and_then(|configuration| configuration.filters.last_mut())
+                        .filter(|filter| filter.name == name);
+                    let Some(filter) = filter else {
+                        bail!("Options {:?} do not belong to the preceding filter", name);
+                    };
+                    filter.options = Some(FilterOptions::from_element(object));
+                }
+            }
+        }
+
+        if options.configurations.is_empty() {
+            bail!("No configurations found");
+        }
+        if options.active_configuration >= options.configurations.len() {
+            options.active_configuration = 0;
+        }
+
+        Ok(options)
+    }
+
+    pub fn active(&self) -> Option<&HctConfiguration> {
+        self.configurations.get(self.active_configuration)
+    }
+
+    /// Filter names of the active configuration, for attributing log lines
+    pub fn filter_names(&self) -> Vec<&str> {
+        self.active()
+            .map(|configuration| {
+                configuration
+                    .filters
+                    .iter()
+                    .map(|filter| filter.name.as_str())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// A single-configuration option set
+    pub fn new(configuration: HctConfiguration) -> Self {
+        Self {
+            filter_manager_version: FILTER_MANAGER_VERSION,
+            active_configuration: 0,
+            configurations: vec![configuration],
+        }
+    }
+
+    /// Serialize to the `.hko` XML layout
+    pub fn to_hko_string(&self) -> String {
+        let mut root = XmlElement::new("hkoptions").with_child(
+            XmlElement::new("hkobject")
+                .with_attribute("class", "hctConfigurationSetData")
+                .with_child(param(
+                    "filterManagerVersion",
+                    self.filter_manager_version.to_string(),
+                ))
+                .with_child(param(
+                    "activeConfiguration",
+                    self.active_configuration.to_string(),
+                )),
+        );
+
+        for configuration in &self.configurations {
+            root = root.with_child(
+                XmlElement::new("hkobject")
+                    .with_attribute("class", "hctConfigurationData")
+                    .with_child(param("configurationName", &configuration.name))
+                    .with_child(param("numFilters", configuration.filters.len().to_string())),
+            );
+            for filter in &configuration.filters {
+                root = root.with_child(
+                    XmlElement::new("hkobject")
+                        .with_attribute("name", &filter.name)
+                        .with_attribute("class", "hctFilterData")
+                        .with_child(param("id", filter.id.to_string()))
+                        .with_child(param("ver", filter.version.to_string()))
+                        .with_child(param("hasOptions", bool_str(filter.options.is_some()))),
+                );
+                if let Some(options) = &filter.options {
+                    root = root.with_child(options.to_element(&filter.name));
+                }
+            }
+        }
+
+        root.to_document_string()
+    }
+}
+
+impl HctConfiguration {
+    /// Options of this configuration's "Write to Platform" filter
+    fn platform_writer(&self) -> Option<&FilterOptions> {
+        self.filters
+            .iter()
+            .find_map(|filter| match &filter.options {
+                Some(options @ FilterOptions::PlatformWriter(_)) => Some(options),
+                Some(options @ FilterOptions::Raw(element))
+                    if element.attribute("class") == Some("hctPlatformWriterOptions") =>
+                {
+                    Some(options)
+                }
+                _ => None,
+            })
+    }
+
+    /// Format the "Write to Platform" filter writes, if it is one Skyrim reads
+    pub fn output_format(&self) -> Option<OutputFormat> {
+        match self.platform_writer()? {
+            FilterOptions::PlatformWriter(options) => options.output_format(),
+            FilterOptions::Raw(element) => {
+                let params = Params::of(element);
+                format_of(
+                    params.boolean("xmlFormat", false),
+                    params.number("bytesInPointer", 4),
+                )
+            }
+            _ => None,
+        }
+    }
+
+    /// File the "Write to Platform" filter writes, relative to the `.hko` file
+    pub fn output_filename(&self) -> Option<String> {
+        match self.platform_writer()? {
+            FilterOptions::PlatformWriter(options) => Some(options.filename.clone()),
+            FilterOptions::Raw(element) => Some(Params::of(element).string("filename", "")),
+            _ => None,
+        }
+        .filter(|filename| !filename.is_empty())
+    }
+}
+
+impl FilterOptions {
+    fn from_element(element: &XmlElement) -> Self {
+        let params = Params::of(element);
+        match element.attribute("class").unwrap_or_default() {
+            "hctMergeAssetOptions" => FilterOptions::MergeAsset(MergeAssetOptions {
+                filename: params.string("filename", "Replace"),
+            }),
+            "hctPruneTypesOptions" => {
+                let mut options = PruneTypesOptions::default();
+                for (name, flag) in options.flags_mut() {
+                    *flag = params.boolean(name, *flag);
+                }
+                options.custom_classes = params.string("customClasses", "");
+                options.selection_sets = params.string("selectionSets", "");
+                options.selection_deletion_mode =
+                    params.string("selectionDeletionMode", &options.selection_deletion_mode);
+                FilterOptions::PruneTypes(options)
+            }
+            "hctPlatformWriterOptions" => {
+                // Presets we don't know are passed through untouched
+                let preset = params.string("preset", PlatformPreset::MsvcWin32.as_str());
+                let Some(preset) = PlatformPreset::parse(&preset) else {
+                    return FilterOptions::Raw(element.clone());
+                };
+                let defaults = PlatformWriterOptions::default();
+                FilterOptions::PlatformWriter(PlatformWriterOptions {
+                    filename: params.string("filename", &defaults.filename),
+                    tagfile: params.boolean("tagfile", defaults.tagfile),
+                    preset,
+                    bytes_in_pointer: params.number("bytesInPointer", defaults.bytes_in_pointer),
+                    little_endian: params.boolean("littleEndian", defaults.little_endian),
+                    reuse_padding_optimized: params
+                        .boolean("reusePaddingOptimized", defaults.reuse_padding_optimized),
+                    empty_base_class_optimized: params.boolean(
+                        "emptyBaseClassOptimized",
+                        defaults.empty_base_class_optimized,
+                    ),
+                    remove_metadata: params.boolean("removeMetadata", defaults.remove_metadata),
+                    user_tag: params.number("userTag", defaults.user_tag),
+                    save_environment_data: params
+                        .boolean("saveEnvironmentData", defaults.save_environment_data),
+                    xml_format: params.boolean("xmlFormat", defaults.xml_format),
+                })
+            }
+            _ => FilterOptions::Raw(element.clone()),
+        }
+    }
+
+    fn class_name(&self) -> &str {
+        match self {
+            FilterOptions::MergeAsset(_) => "hctMergeAssetOptions",
+            FilterOptions::PruneTypes(_) => "hctPruneTypesOptions",
+            FilterOptions::PlatformWriter(_) => "hctPlatformWriterOptions",
+            FilterOptions::Raw(element) => element.attribute("class").unwrap_or_default(),
+        }
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        match self {
+            FilterOptions::MergeAsset(options) => vec![("filename", options.filename.clone())],
+            FilterOptions::PruneTypes(options) => vec![
+                ("pruneSceneData", bool_str(options.prune_scene_data)),
+                ("pruneMeshData", bool_str(options.prune_mesh_data)),
+                ("pruneSkeletonData", bool_str(options.prune_skeleton_data)),
+                ("pruneAnimationData", bool_str(options.prune_animation_data)),
+                ("customClasses", options.custom_classes.clone()),
+                ("pruneAllSceneData", bool_str(options.prune_all_scene_data)),
+                (
+                    "pruneEnvironmentData",
+                    bool_str(options.prune_environment_data),
+                ),
+                ("pruneResourceData", bool_str(options.prune_resource_data)),
+                (
+                    "pruneDestructionData",
+                    bool_str(options.prune_destruction_data),
+                ),
+                (
+                    "pruneAnimationTracks",
+                    bool_str(options.prune_animation_tracks),
+                ),
+                ("pruneAnnotations", bool_str(options.prune_annotations)),
+                (
+                    "pruneIdentityBindingIndices",
+                    bool_str(options.prune_identity_binding_indices),
+                ),
+                (
+                    "pruneQuantizedBindings",
+                    bool_str(options.prune_quantized_bindings),
+                ),
+                ("pruneAttributes", bool_str(options.prune_attributes)),
+                (
+                    "pruneMeshUserChannels",
+                    bool_str(options.prune_mesh_user_channels),
+                ),
+                ("pruneAttachments", bool_str(options.prune_attachments)),
+                ("pruneSelectionSets", bool_str(options.prune_selection_sets)),
+                ("selectionSets", options.selection_sets.clone()),
+                (
+                    "selectionDeletionMode",
+                    options.selection_deletion_mode.clone(),
+                ),
+                (
+                    "pruneAllAnimationData",
+                    bool_str(options.prune_all_animation_data),
+                ),
+                (
+                    "pruneMeshBindingData",
+                    bool_str(options.prune_mesh_binding_data),
+                ),
+                (
+                    "pruneRagdollAndMapperData",
+                    bool_str(options.prune_ragdoll_and_mapper_data),
+                ),
+            ],
+            // littleEndian and the layout optimizations are written as 0/1 by HCT
+            FilterOptions::PlatformWriter(options) => vec![
+                ("filename", options.filename.clone()),
+                ("tagfile", bool_str(options.tagfile)),
+                ("preset", options.preset.as_str().to_string()),
+                ("bytesInPointer", options.bytes_in_pointer.to_string()),
+                ("littleEndian", bit_str(options.little_endian)),
+                (
+                    "reusePaddingOptimized",
+                    bit_str(options.reuse_padding_optimized),
+                ),
+                (
+                    "emptyBaseClassOptimized",
+                    bit_str(options.empty_base_class_optimized),
+                ),
+                ("removeMetadata", bool_str(options.remove_metadata)),
+                ("userTag", options.user_tag.to_string()),
+                (
+                    "saveEnvironmentData",
+                    bool_str(options.save_environment_data),
+                ),
+                ("xmlFormat", bool_str(options.xml_format)),
+            ],
+            FilterOptions::Raw(_) => Vec::new(),
+        }
+    }
+
+    fn to_element(&self, filter_name: &str) -> XmlElement {
+        if let FilterOptions::Raw(element) = self {
+            return element.clone();
+        }
+
+        let mut element = XmlElement::new("hkobject")
+            .with_attribute("name", filter_name)
+            .with_attribute("class", self.class_name());
+        for (name, value) in self.params() {
+            element = element.with_child(param(name, value));
+        }
+        element
+    }
+}
+
+/// Per-batch HCT settings edited in the GUI
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct HctSettings {
+    pub prune_types: PruneTypesOptions,
+    pub platform_writer: PlatformWriterOptions,
+}
+
+impl HctSettings {
+    /// Merge Asset -> Prune Types -> Write to Platform, as the original `_SSEtoLE.hko` did
+    pub fn to_options(&self) -> HkoOptions {
+        HkoOptions::new(HctConfiguration {
+            name: if self.platform_writer.xml_format {
+                "ConvertAnimation_xml".to_string()
+            } else {
+                format!(
+                    "ConvertAnimation_x{}",
+                    self.platform_writer.bytes_in_pointer as u32 * 8
+                )
+            },
+            filters: vec![
+                HctFilter::merge_asset(MergeAssetOptions::default()),
+                HctFilter::prune_types(self.prune_types.clone()),
+                HctFilter::write_to_platform(self.platform_writer.clone()),
+            ],
+        })
+    }
+}
+
+/// A user's own `.hko` file, with the configuration picked in the GUI
+#[derive(Debug, Clone)]
+pub struct CustomHko {
+    pub path: PathBuf,
+    pub options: HkoOptions,
+}
+
+impl CustomHko {
+    pub fn load(path: PathBuf) -> Result<Self> {
+        let options = HkoOptions::load(&path)?;
+        Ok(Self { path, options })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// A warning or error from the filter manager's log
+#[derive(Debug, Clone)]
+pub struct HctDiagnostic {
+    /// Filter that was running when the line was logged, e.g. "Prune Types"
+    pub filter: Option<String>,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// Pick the warnings and errors out of the filter manager's output.
+///
+/// The filter manager announces each filter by name before it runs, so a line is attributed
+/// to the most recently mentioned filter.
+pub fn parse_filter_log(log: &str, filter_names: &[&str]) -> Vec<HctDiagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut current_filter: Option<&str> = None;
+
+    for line in log.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        if let Some(name) = filter_names.iter().find(|name| line.contains(**name)) {
+            current_filter = Some(name);
+        }
+
+        let lower = line.to_lowercase();
+        let severity = if lower.contains("error") || lower.contains("failed") {
+            Severity::Error
+        } else if lower.contains("warning") {
+            Severity::Warning
+        } else {
+            continue;
+        };
+
+        diagnostics.push(HctDiagnostic {
+            filter: current_filter.map(str::to_string),
+            severity,
+            message: line.to_string(),
+        });
+    }
+
+    diagnostics
+}
+
+/// A failed HCT run; its temporary directory is kept so the files can be inspected
+#[derive(Debug)]
+pub struct HctFailure {
+    pub message: String,
+    pub diagnostics: Vec<HctDiagnostic>,
+    pub temp_dir: PathBuf,
+    pub run: ToolRun,
+}
+
+impl std::fmt::Display for HctFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} (files kept in {})",
+            self.message,
+            self.temp_dir.display()
+        )
+    }
+}
+
+impl std::error::Error for HctFailure {}
+
+/// Result of looking for `hctStandAloneFilterManager.exe`
+#[derive(Debug, Clone, Default)]
+pub struct HctLookup {
+    pub found: Option<PathBuf>,
+    /// Every location that was checked, in order, for explaining a failed lookup
+    pub searched: Vec<PathBuf>,
+}
+
+impl HctLookup {
+    /// Look for HCT at the explicit path if one is set; otherwise under `HAVOK_TOOLS_ROOT`,
+    /// the default install folders and finally `PATH`.
+    pub fn locate(explicit: Option<&Path>) -> Self {
+        let mut candidates = Vec::new();
+
+        if let Some(explicit) = explicit {
+            // Accept either the exe itself or the folder containing it
+            candidates.push(if explicit.is_dir() {
+                explicit.join(FILTER_MANAGER_EXE)
+            } else {
+                explicit.to_path_buf()
+            });
+        } else {
+            if let Some(root) = std::env::var_os("HAVOK_TOOLS_ROOT") {
+                candidates.push(PathBuf::from(root).join(FILTER_MANAGER_EXE));
+            }
+            for program_files in ["ProgramW6432", "ProgramFiles", "ProgramFiles(x86)"] {
+                if let Some(folder) = std::env::var_os(program_files) {
+                    candidates.push(
+                        PathBuf::from(folder)
+                            .join("Havok")
+                            .join("HavokContentTools")
+                            .join(FILTER_MANAGER_EXE),
+                    );
+                }
+            }
+            if let Some(path) = std::env::var_os("PATH") {
+                candidates.extend(
+                    std::env::split_paths(&path).map(|folder| folder.join(FILTER_MANAGER_EXE)),
+                );
+            }
+            candidates.dedup();
+        }
+
+        let found = candidates
+            .iter()
+            .find(|candidate| candidate.is_file())
+            .cloned();
+        Self {
+            found,
+            searched: candidates,
+        }
+    }
+
+    /// Why HCT is unavailable, for tooltips and error messages
+    pub fn explanation(&self) -> String {
+        if let Some(found) = &self.found {
+            return format!("Using {}", found.display());
+        }
+
+        let mut text = format!(
+            "{} was not found. Install Havok Content Tools, set HAVOK_TOOLS_ROOT, or set its path in Settings.",
+            FILTER_MANAGER_EXE
+        );
+        if !self.searched.is_empty() {
+            text.push_str("\nSearched:");
+            for candidate in self.searched.iter().take(8) {
+                text.push_str(&format!("\n  {}", candidate.display()));
+            }
+            if self.searched.len() > 8 {
+                text.push_str(&format!("\n  ...and {} more", self.searched.len() - 8));
+            }
+        }
+        text
+    }
+}
+
+/// `hkparam` values of an options object, by name
+struct Params(HashMap<String, String>);
+
+impl Params {
+    fn of(object: &XmlElement) -> Self {
+        Self(
+            object
+                .elements()
+                .filter(|e| e.name == "hkparam")
+                .filter_map(|e| Some((e.attribute("name")?.to_string(), e.text())))
+                .collect(),
+        )
+    }
+
+    fn string(&self, name: &str, default: &str) -> String {
+        self.0
+            .get(name)
+            .map_or_else(|| default.to_string(), |value| value.trim().to_string())
+    }
+
+    fn boolean(&self, name: &str, default: bool) -> bool {
+        match self.0.get(name).map(|value| value.trim()) {
+            Some("true" | "1") => true,
+            Some("false" | "0") => false,
+            _ => default,
+        }
+    }
+
+    fn number<T: std::str::FromStr>(&self, name: &str, default: T) -> T {
+        self.0
+            .get(name)
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or(default)
+    }
+}
+
+fn param(name: &str, value: impl Into<String>) -> XmlElement {
+    let value = value.into();
+    let element = XmlElement::new("hkparam").with_attribute("name", name);
+    if value.is_empty() {
+        element
+    } else {
+        element.with_text(value)
+    }
+}
+
+fn bool_str(value: bool) -> String {
+    if value { "true" } else { "false" }.to_string()
+}
+
+fn bit_str(value: bool) -> String {
+    if value { "1" } else { "0" }.to_string()
+}
@@ -0,0 +1,323 @@
+//! Object graph of a Havok packfile.
+//!
+//! Binary packfiles (LE or SE) are read through serde_hkx by converting them to XML first,
+//! so every file ends up in the same `hkpackfile` XML shape before it is turned into
+//! [`HkxDocument`].
+
+use crate::xml::{self, XmlElement, XmlNode};
+use anyhow::{bail, Context as AnyhowContext, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Default)]
+pub struct HkxDocument {
+    pub class_version: Option<String>,
+    pub contents_version: Option<String>,
+    pub top_level_object: Option<String>,
+    pub objects: Vec<HkxObject>,
+    /// Object name (`#0042`) -> index into `objects`
+    index: HashMap<String, usize>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HkxObject {
+    /// Pointer name such as `#0042`; `None` for inline struct members
+    pub name: Option<String>,
+    pub class: Option<String>,
+    pub signature: Option<String>,
+    pub params: Vec<HkxParam>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HkxParam {
+    pub name: String,
+    pub num_elements: Option<usize>,
+    pub value: HkxValue,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HkxValue {
+    /// Scalars, strings, vectors and pointer lists
+    Text(String),
+    /// Arrays of strings, written as one `<hkcstring>` element each
+    Strings(Vec<String>),
+    /// Inline structs, e.g. the elements of an array of `hkbVariableInfo`
+    Objects(Vec<HkxObject>),
+}
+
+impl HkxDocument {
+    /// Load an LE/SE packfile or a Havok XML file. The content decides which, since outputs
+    /// may have been written with any extension.
+    pub async fn load(path: &Path) -> Result<Self> {
+        let bytes = fs::read(path).with_context(|| format!("Failed to read {:?}", path))?;
+        let bytes = if looks_like_xml(&bytes) {
+            bytes
+        } else {
+            // serde_hkx goes by the extension, so hand it a copy named as a packfile
+            let temp_dir = tempfile::Builder::new()
+                .prefix("hkx_document_")
+                .tempdir()
+                .context("Failed to create temporary directory for XML conversion")?;
+            let hkx_path = temp_dir.path().join("document.hkx");
+            fs::write(&hkx_path, &bytes).context("Failed to copy the packfile")?;
+            let xml_path = temp_dir.path().join("document.xml");
+            serde_hkx_features::convert::tokio::convert(
+                &hkx_path,
+                Some(&xml_path),
+                serde_hkx_features::OutFormat::Xml,
+            )
+            .await
+            .with_context(|| format!("serde_hkx failed to read {:?}", path))?;
+            fs::read(&xml_path).context("serde_hkx did not produce XML output")?
+        };
+
+        Self::from_xml(&String::from_utf8_lossy(&bytes))
+            .with_context(|| format!("Failed to parse {:?}", path))
+    }
+
+    pub fn from_xml(text: &str) -> Result<Self> {
+        let root = xml::parse(text)?;
+        if root.name != "hkpackfile" {
+            bail!("Not a Havok packfile: root element is <{}>", root.name);
+        }
+
+        let mut document = Self {
+            class_version: root.attribute("classversion").map(str::to_string),
+            contents_version: root.attribute("contentsversion").map(str::to_string),
+            top_level_object: root.attribute("toplevelobject").map(str::to_string),
+            ..Default::default()
+        };
+
+        for section in root.elements().filter(|e| e.name == "hksection") {
+            for element in section.elements().filter(|e| e.name == "hkobject") {
+                document.objects.push(HkxObject::from_element(element));
+            }
+        }
+        document.rebuild_index();
+
+        Ok(document)
+    }
+
+    /// Serialize the whole document as a Havok XML packfile with a single `__data__` section
+    pub fn to_xml(&self) -> String {
+        let mut root = XmlElement::new("hkpackfile");
+        for (name, value) in [
+            ("classversion", &self.class_version),
+            ("contentsversion", &self.contents_version),
+            ("toplevelobject", &self.top_level_object),
+        ] {
+            if let Some(value) = value {
+                root = root.with_attribute(name, value);
+            }
+        }
+        let mut section = XmlElement::new("hksection").with_attribute("name", "__data__");
+        for object in &self.objects {
+            section = section.with_child(object.to_element());
+        }
+        root.with_child(section).to_document_string()
+    }
+
+    pub fn rebuild_index(&mut self) {
+        self.index = self
+            .objects
+            .iter()
+            .enumerate()
+            .filter_map(|(index, object)| Some((object.name.clone()?, index)))
+            .collect();
+    }
+
+    pub fn object(&self, name: &str) -> Option<&HkxObject> {
+        self.index.get(name).map(|&index| &self.objects[index])
+    }
+
+    /// Top-level objects of one class, in file order
+    pub fn objects_of_class<'a>(&'a self, class: &'a str) -> impl Iterator<Item = &'a HkxObject> {
+        self.objects
+            .iter()
+            .filter(move |object| object.class.as_deref() == Some(class))
+    }
+}
+
+impl HkxObject {
+    fn from_element(element: &XmlElement) -> Self {
+        let params = element
+            .elements()
+            .filter(|e| e.name == "hkparam")
+            .map(HkxParam::from_element)
+            .collect();
+
+        Self {
+            name: element.attribute("name").map(str::to_string),
+            class: element.attribute("class").map(str::to_string),
+            signature: element.attribute("signature").map(str::to_string),
+            params,
+        }
+    }
+
+    fn to_element(&self) -> XmlElement {
+        let mut element = XmlElement::new("hkobject");
+        if let Some(name) = &self.name {
+            element = element.with_attribute("name", name);
+        }
+        if let Some(class) = &self.class {
+            element = element.with_attribute("class", class);
+        }
+        if let Some(signature) = &self.signature {
+            element = element.with_attribute("signature", signature);
+        }
+        for param in &self.params {
+            element = element.with_child(param.to_element());
+        }
+        element
+    }
+
+    /// Serialize this object (and its inline members) as Havok XML
+    pub fn to_xml(&self) -> String {
+        self.to_xml_at(0)
+    }
+
+    /// Serialize indented by `depth` tabs; objects sit at depth 2 inside a packfile
+    pub fn to_xml_at(&self, depth: usize) -> String {
+        let mut out = String::new();
+        self.to_element().write(&mut out, depth);
+        out
+    }
+
+    pub fn param(&self, name: &str) -> Option<&HkxParam> {
+        self.params.iter().find(|param| param.name == name)
+    }
+
+    /// Text value of a parameter, if it has one
+    pub fn text(&self, name: &str) -> Option<&str> {
+        match &self.param(name)?.value {
+            HkxValue::Text(text) => Some(text.trim()),
+            HkxValue::Strings(_) | HkxValue::Objects(_) => None,
+        }
+    }
+
+    /// Elements of a string array parameter; a plain string parameter counts as one
+    pub fn strings(&self, name: &str) -> Vec<&str> {
+        match self.param(name).map(|param| &param.value) {
+            Some(HkxValue::Strings(strings)) => strings.iter().map(|s| s.trim()).collect(),
+            Some(HkxValue::Text(text)) if !text.trim().is_empty() => vec![text.trim()],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Number of elements in an array parameter
+    pub fn array_len(&self, name: &str) -> Option<usize> {
+        let param = self.param(name)?;
+        if let Some(num_elements) = param.num_elements {
+            return Some(num_elements);
+        }
+        Some(match &param.value {
+            HkxValue::Text(text) => text.split_whitespace().count(),
+            HkxValue::Strings(strings) => strings.len(),
+            HkxValue::Objects(objects) => objects.len(),
+        })
+    }
+
+    /// Pointer references in this object and its inline members
+    pub fn references(&self) -> Vec<&str> {
+        let mut references = Vec::new();
+        for param in &self.params {
+            match &param.value {
+                HkxValue::Text(text) => references.extend(pointer_references(text)),
+                HkxValue::Strings(_) => {}
+                HkxValue::Objects(members) => {
+                    for member in members {
+                        references.extend(member.references());
+                    }
+                }
+            }
+        }
+        references
+    }
+
+    /// Display label, e.g. `#0042 hkbStateMachine`
+    pub fn label(&self) -> String {
+        match (&self.name, &self.class) {
+            (Some(name), Some(class)) => format!("{} {}", name, class),
+            (Some(name), None) => name.clone(),
+            (None, Some(class)) => class.clone(),
+            (None, None) => "hkobject".to_string(),
+        }
+    }
+}
+
+impl HkxParam {
+    fn from_element(element: &XmlElement) -> Self {
+        let objects: Vec<_> = element
+            .elements()
+            .filter(|e| e.name == "hkobject")
+            .map(HkxObject::from_element)
+            .collect();
+
+        let strings: Vec<_> = element
+            .elements()
+            .filter(|e| e.name == "hkcstring")
+            .map(XmlElement::text)
+            .collect();
+
+        let value = if !strings.is_empty() {
+            HkxValue::Strings(strings)
+        } else if objects.is_empty() {
+            HkxValue::Text(element.text())
+        } else {
+            HkxValue::Objects(objects)
+        };
+
+        Self {
+            name: element.attribute("name").unwrap_or_default().to_string(),
+            num_elements: element
+                .attribute("numelements")
+                .and_then(|n| n.parse().ok()),
+            value,
+        }
+    }
+
+    fn to_element(&self) -> XmlElement {
+        let mut element = XmlElement::new("hkparam").with_attribute("name", &self.name);
+        if let Some(num_elements) = self.num_elements {
+            element = element.with_attribute("numelements", num_elements.to_string());
+        }
+        match &self.value {
+            HkxValue::Text(text) if text.is_empty() => element,
+            HkxValue::Text(text) => element.with_text(text.as_str()),
+            HkxValue::Strings(strings) => {
+                for string in strings {
+                    element = element.with_child(XmlElement::new("hkcstring").with_text(string));
+                }
+                element
+            }
+            HkxValue::Objects(objects) => {
+                for object in objects {
+                    element.children.push(XmlNode::Element(object.to_element()));
+                }
+                element
+            }
+        }
+    }
+}
+
+/// Whether `bytes` is XML text (`<?xml`, `<hkpackfile`, ...) rather than a binary file
+pub fn looks_like_xml(bytes: &[u8]) -> bool {
+    let bytes = bytes.strip_prefix(b"\xef\xbb\xbf").unwrap_or(bytes);
+    bytes
+        .iter()
+        .find(|byte| !byte.is_ascii_whitespace())
+        .is_some_and(|&byte| byte == b'<')
+}
+
+/// Whether a token is a pointer reference such as `#0042`
+pub fn is_pointer(token: &str) -> bool {
+    token
+        .strip_prefix('#')
+        .is_some_and(|digits| !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// All pointer references in a parameter's text
+pub fn pointer_references(text: &str) -> impl Iterator<Item = &str> {
+    text.split_whitespace().filter(|token| is_pointer(token))
+}
@@ -0,0 +1,265 @@
+//! Converting files in place: each original is backed up, the converted file replaces it,
+//! and the whole batch can be restored from the backups afterwards.
+//!
+//! Backups go to a folder per batch under the settings folder, together with a manifest of
+//! what was replaced, so an undo still works after the program was closed or crashed.
+
+use crate::settings::Settings;
+use anyhow::{Context as AnyhowContext, Result};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const MANIFEST: &str = "manifest.txt";
+
+/// An original file replaced by an in-place conversion
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplacedFile {
+    pub original: PathBuf,
+    pub backup: PathBuf,
+    /// Where the converted file was written; differs from `original` when the extension changed
+    pub output: PathBuf,
+    /// Backup of a file that already existed at `output` and was overwritten
+    pub output_backup: Option<PathBuf>,
+}
+
+impl ReplacedFile {
+    fn to_line(&self) -> String {
+        let path = |path: &Path| path.to_string_lossy().to_string();
+        format!(
+            "{}\t{}\t{}\t{}\n",
+            path(&self.original),
+            path(&self.backup),
+            path(&self.output),
+            self.output_backup.as_deref().map(path).unwrap_or_default()
+        )
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let mut fields = line.split('\t');
+        let original = PathBuf::from(fields.next()?);
+        let backup = PathBuf::from(fields.next()?);
+        let output = PathBuf::from(fields.next()?);
+        let output_backup = fields
+            .next()
+            .filter(|field| !field.is_empty())
+            .map(PathBuf::from);
+        Some(Self {
+            original,
+            backup,
+            output,
+            output_backup,
+        })
+    }
+
+    fn backup_dir(&self) -> &Path {
+        self.backup.parent().unwrap_or(Path::new(""))
+    }
+}
+
+fn backups_root() -> Result<PathBuf> {
+    Ok(Settings::folder()
+        .context("Could not determine settings folder")?
+        .join("in_place_backups"))
+}
+
+/// Create the folder the originals of one batch are backed up to
+pub fn create_backup_dir() -> Result<PathBuf> {
+    let root = backups_root()?;
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    // Two batches in the same second get numbered folders
+    let dir = (0..)
+        .map(|n| root.join(format!("{}_{}", stamp, n)))
+        .find(|dir| !dir.exists())
+        .unwrap_or_default();
+    fs::create_dir_all(&dir).context("Failed to create backup directory")?;
+    Ok(dir)
+}
+
+/// Back up `original`, and any other file at `output`, then move the already converted
+/// `converted` file to `output`. An original with a different name than its output stays in
+/// place until [`remove_originals`] is called for the finished batch. `index` keeps backups
+/// of files with the same name apart.
+pub fn replace(
+    original: &Path,
+    converted: &Path,
+    output: &Path,
+    backup_dir: &Path,
+    index: usize,
+) -> Result<ReplacedFile> {
+    let file_name = original.file_name().unwrap_or_default().to_string_lossy();
+    let backup = backup_dir.join(format!("{}_{}", index, file_name));
+    fs::copy(original, &backup).with_context(|| format!("Failed to back up {:?}", original))?;
+
+    let output_backup = if output != original && output.exists() {
+        let output_name = output.file_name().unwrap_or_default().to_string_lossy();
+        let output_backup = backup_dir.join(format!("{}_output_{}", index, output_name));
+        fs::copy(output, &output_backup)
+            .with_context(|| format!("Failed to back up existing {:?}", output))?;
+        Some(output_backup)
+    } else {
+        None
+    };
+
+    let replaced = ReplacedFile {
+        original: original.to_path_buf(),
+        backup,
+        output: output.to_path_buf(),
+        output_backup,
+    };
+    // Recorded before anything is overwritten, so a crash can still be undone
+    append_to_manifest(backup_dir, &replaced)?;
+
+    // A rename fails across drives, so fall back to copying
+    if fs::rename(converted, output).is_err() {
+        fs::copy(converted, output)
+            .with_context(|| format!("Failed to write converted file to {:?}", output))?;
+    }
+
+    Ok(replaced)
+}
+
+fn append_to_manifest(backup_dir: &Path, file: &ReplacedFile) -> Result<()> {
+    let path = backup_dir.join(MANIFEST);
+    let mut manifest = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open {:?}", path))?;
+    manifest
+        .write_all(file.to_line().as_bytes())
+        .with_context(|| format!("Failed to write {:?}", path))
+}
+
+/// Remove the originals whose converted file got another name, once the whole batch has
+/// been converted
+pub fn remove_originals(files: &[ReplacedFile]) -> Result<()> {
+    for file in files {
+        if file.output != file.original && file.original.exists() {
+            fs::remove_file(&file.original)
+                .with_context(|| format!("Failed to remove original {:?}", file.original))?;
+        }
+    }
+    Ok(())
+}
+
+/// Replaced files of earlier batches that have not been undone, oldest first
+pub fn load_pending() -> Vec<ReplacedFile> {
+    let Ok(entries) = backups_root().and_then(|root| Ok(fs::read_dir(root)?)) else {
+        return Vec::new();
+    };
+    let mut dirs = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|dir| dir.join(MANIFEST).exists())
+        .collect::<Vec<_>>();
+    dirs.sort();
+
+    dirs.iter()
+        .filter_map(|dir| fs::read_to_string(dir.join(MANIFEST)).ok())
+        .flat_map(|manifest| {
+            manifest
+                .lines()
+                .filter_map(ReplacedFile::from_line)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Put every original back and remove the converted files that were written next to them.
+/// Returns the number of restored files; files that could not be restored are reported in
+/// the error and left in `files`.
+pub fn undo(files: &mut Vec<ReplacedFile>) -> Result<usize> {
+    let mut restored = 0;
+    let mut failed = Vec::new();
+    let mut errors = Vec::new();
+    let mut backup_dirs = files
+        .iter()
+        .map(|file| file.backup_dir().to_path_buf())
+        .collect::<Vec<_>>();
+    backup_dirs.sort();
+    backup_dirs.dedup();
+
+    // Newest first, so a file converted twice ends up as its first original
+    for file in files.drain(..).rev() {
+        match restore(&file) {
+            Ok(()) => restored += 1,
+            Err(e) => {
+                errors.push(format!("{:#}", e));
+                failed.push(file);
+            }
+        }
+    }
+    failed.reverse();
+    *files = failed;
+
+    // Backups of restored files are no longer needed; the rest stay listed for another try
+    for dir in backup_dirs {
+        let remaining = files
+            .iter()
+            .filter(|file| file.backup_dir() == dir)
+            .collect::<Vec<_>>();
+        let result = if remaining.is_empty() {
+            fs::remove_dir_all(&dir)
+        } else {
+            fs::write(
+                dir.join(MANIFEST),
+                remaining
+                    .iter()
+                    .map(|file| file.to_line())
+                    .collect::<String>(),
+            )
+        };
+        if let Err(e) = result {
+            errors.push(format!("Failed to update backups in {:?}: {}", dir, e));
+        }
+    }
+
+    if !errors.is_empty() {
+        anyhow::bail!(
+            "Restored {} files, {} failed: {}",
+            restored,
+            errors.len(),
+            errors.join("; ")
+        );
+    }
+    Ok(restored)
+}
+
+/// Keep the converted files and delete the backups of `files`
+pub fn discard_backups(files: &mut Vec<ReplacedFile>) -> Result<()> {
+    let mut backup_dirs = files
+        .iter()
+        .map(|file| file.backup_dir().to_path_buf())
+        .collect::<Vec<_>>();
+    backup_dirs.sort();
+    backup_dirs.dedup();
+    for dir in backup_dirs {
+        fs::remove_dir_all(&dir).with_context(|| format!("Failed to remove {:?}", dir))?;
+    }
+    files.clear();
+    Ok(())
+}
+
+fn restore(file: &ReplacedFile) -> Result<()> {
+    fs::copy(&file.backup, &file.original)
+        .with_context(|| format!("Failed to restore {:?}", file.original))?;
+    if file.output != file.original {
+        match &file.output_backup {
+            Some(output_backup) => {
+                fs::copy(output_backup, &file.output)
+                    .with_context(|| format!("Failed to restore {:?}", file.output))?;
+            }
+            None if file.output.exists() => {
+                fs::remove_file(&file.output).with_context(|| {
+                    format!("Failed to remove converted file {:?}", file.output)
+                })?;
+            }
+            None => {}
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,214 @@
+//! Include and exclude glob patterns for input files, such as `_1stperson/**` or
+//! `*_backup.hkx`.
+//!
+//! Patterns are separated by `;` and matched case-insensitively. `*` and `?` stay within one
+//! folder, `**` spans any number of folders. A pattern without `/` matches the file name; one
+//! with `/` matches at any depth below the folder being added, or only at its top when it
+//! starts with `/`.
+
+use crate::bsa;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::oneshot;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Glob {
+    anchored: bool,
+    components: Vec<String>,
+}
+
+impl Glob {
+    fn parse(pattern: &str) -> Option<Self> {
+        let pattern = pattern.trim().replace('\\', "/").to_lowercase();
+        let anchored = pattern.starts_with('/');
+        let components = pattern
+            .split('/')
+            .filter(|component| !component.is_empty())
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        (!components.is_empty()).then_some(Self {
+            anchored,
+            components,
+        })
+    }
+
+    fn matches(&self, path: &[String]) -> bool {
+        if self.components.len() == 1 && !self.anchored {
+            return path
+                .last()
+                .is_some_and(|name| match_component(&self.components[0], name));
+        }
+        if self.anchored {
+            return match_components(&self.components, path);
+        }
+        (0..path.len()).any(|start| match_components(&self.components, &path[start..]))
+    }
+}
+
+fn match_components(pattern: &[String], path: &[String]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((first, rest)) if first == "**" => {
+            (0..=path.len()).any(|skip| match_components(rest, &path[skip..]))
+        }
+        Some((first, rest)) => path.split_first().is_some_and(|(name, path_rest)| {
+            match_component(first, name) && match_components(rest, path_rest)
+        }),
+    }
+}
+
+/// `*` and `?` wildcards within one file or folder name
+fn match_component(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+    let (mut p, mut t) = (0, 0);
+    // Where the last `*` was and how much text it has taken so far
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+fn parse_list(patterns: &str) -> Vec<Glob> {
+    patterns.split(';').filter_map(Glob::parse).collect()
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InputFilter {
+    include: Vec<Glob>,
+    exclude: Vec<Glob>,
+}
+
+impl InputFilter {
+    pub fn new(include: &str, exclude: &str) -> Self {
+        Self {
+            include: parse_list(include),
+            exclude: parse_list(exclude),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// Whether `path`, added from `root`, matches an include pattern (or there are none) and
+    /// no exclude pattern. Without a root, archive entries are matched by their path inside
+    /// the archive and other files by their full path.
+    pub fn accepts(&self, path: &Path, root: Option<&Path>) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+        let components = match (root, bsa::split_archive_path(path)) {
+            (Some(root), _) if path.starts_with(root) => {
+                lowercase_components(path.strip_prefix(root).unwrap_or(path))
+            }
+            (_, Some((_, entry_path))) => entry_path
+                .split('\\')
+                .map(|component| component.to_lowercase())
+                .collect(),
+            _ => lowercase_components(path),
+        };
+
+        (self.include.is_empty() || self.include.iter().any(|glob| glob.matches(&components)))
+            && !self.exclude.iter().any(|glob| glob.matches(&components))
+    }
+}
+
+fn lowercase_components(path: &Path) -> Vec<String> {
+    path.components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_string_lossy().to_lowercase()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// How many files of a folder pass the patterns, recounted in the background only when the
+/// folder, patterns or `tag` (anything else deciding which files count) change
+#[derive(Debug, Default)]
+pub struct FolderPreview {
+    key: Option<(PathBuf, bool, InputFilter, String)>,
+    /// `(matched, total)` of the walk in progress
+    counting: Option<oneshot::Receiver<(usize, usize)>>,
+    /// Set to stop a walk whose count is no longer wanted
+    cancel: Arc<AtomicBool>,
+    pub matched: usize,
+    pub total: usize,
+}
+
+impl FolderPreview {
+    pub fn update(
+        &mut self,
+        tokio_handle: &tokio::runtime::Handle,
+        folder: &Path,
+        recursive: bool,
+        filter: &InputFilter,
+        tag: String,
+        counts: impl Fn(&Path) -> bool + Send + 'static,
+    ) {
+        let key = (folder.to_path_buf(), recursive, filter.clone(), tag);
+        if self.key.as_ref() != Some(&key) {
+            // Typing a pattern changes the key on every keystroke; only the last walk counts
+            self.cancel.store(true, Ordering::Relaxed);
+            let cancel = Arc::new(AtomicBool::new(false));
+            self.cancel = cancel.clone();
+
+            let (tx, rx) = oneshot::channel();
+            let folder = folder.to_path_buf();
+            let filter = filter.clone();
+            tokio_handle.spawn_blocking(move || {
+                let walker = walkdir::WalkDir::new(&folder)
+                    .follow_links(true)
+                    .max_depth(if recursive { usize::MAX } else { 1 });
+                let mut matched = 0;
+                let mut total = 0;
+                for entry in walker.into_iter().flatten() {
+                    if cancel.load(Ordering::Relaxed) {
+                        return;
+                    }
+                    let path = entry.path();
+                    if counts(path) {
+                        total += 1;
+                        if filter.accepts(path, Some(&folder)) {
+                            matched += 1;
+                        }
+                    }
+                }
+                let _ = tx.send((matched, total));
+            });
+            self.counting = Some(rx);
+            self.key = Some(key);
+        }
+
+        if let Some(rx) = &mut self.counting {
+            match rx.try_recv() {
+                Ok((matched, total)) => {
+                    self.matched = matched;
+                    self.total = total;
+                    self.counting = None;
+                }
+                Err(oneshot::error::TryRecvError::Empty) => {}
+                Err(oneshot::error::TryRecvError::Closed) => self.counting = None,
+            }
+        }
+    }
+
+    /// Whether a count is still running; the last finished count stays readable meanwhile
+    pub fn is_counting(&self) -> bool {
+        self.counting.is_some()
+    }
+}
@@ -0,0 +1,468 @@
+//! Inspector tab: browse the object graph of an HKX/XML file without converting it first.
+
+use crate::hkx_document::{self, HkxDocument, HkxObject, HkxValue};
+use anyhow::Result;
+use eframe::egui;
+use egui::{Color32, RichText, Ui};
+use rfd::FileDialog;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use tokio::sync::oneshot;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum View {
+    /// Objects nested under the pointers that lead to them, starting at the top level object
+    Tree,
+    /// Every object in file order, filtered by the search
+    List,
+}
+
+pub struct Inspector {
+    path: Option<PathBuf>,
+    document: Option<HkxDocument>,
+    error: Option<String>,
+    search: String,
+    view: View,
+    /// Indices into `document.objects` that match `search`
+    filtered: Vec<usize>,
+    /// Indices of objects the tree can't reach from the top level object
+    unreachable: Vec<usize>,
+    /// Object to expand and scroll to on the next frame
+    jump_to: Option<String>,
+    load_rx: Option<oneshot::Receiver<Result<HkxDocument>>>,
+    tokio_handle: tokio::runtime::Handle,
+}
+
+impl Inspector {
+    pub fn new(tokio_handle: tokio::runtime::Handle) -> Self {
+        Self {
+            path: None,
+            document: None,
+            error: None,
+            search: String::new(),
+            view: View::Tree,
+            filtered: Vec::new(),
+            unreachable: Vec::new(),
+            jump_to: None,
+            load_rx: None,
+            tokio_handle,
+        }
+    }
+
+    /// Start loading a file in the background
+    pub fn open(&mut self, path: PathBuf) {
+        let (load_tx, load_rx) = oneshot::channel();
+        self.load_rx = Some(load_rx);
+        self.document = None;
+        self.error = None;
+        self.jump_to = None;
+        self.path = Some(path.clone());
+
+        self.tokio_handle.spawn(async move {
+            let _ = load_tx.send(HkxDocument::load(&path).await);
+        });
+    }
+
+    fn poll_load(&mut self, ui: &Ui) {
+        let Some(load_rx) = &mut self.load_rx else {
+            return;
+        };
+
+        match load_rx.try_recv() {
+            Ok(Ok(document)) => {
+                let reachable = match &document.top_level_object {
+                    Some(root) => reachable(&document, root),
+                    None => HashSet::new(),
+                };
+                self.unreachable = document
+                    .objects
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, object)| {
+                        !object
+                            .name
+                            .as_ref()
+                            .is_some_and(|name| reachable.contains(name))
+                    })
+                    .map(|(index, _)| index)
+                    .collect();
+                self.document = Some(document);
+                self.load_rx = None;
+                self.update_filter();
+            }
+            Ok(Err(e)) => {
+                self.error = Some(format!("{:#}", e));
+                self.load_rx = None;
+            }
+            Err(oneshot::error::TryRecvError::Empty) => ui.ctx().request_repaint(),
+            Err(oneshot::error::TryRecvError::Closed) => {
+                self.error = Some("Loading was interrupted".to_string());
+                self.load_rx = None;
+            }
+        }
+    }
+
+    fn update_filter(&mut self) {
+        let Some(document) = &self.document else {
+            self.filtered.clear();
+            return;
+        };
+
+        let query = self.search.trim().to_lowercase();
+        self.filtered = document
+            .objects
+            .iter()
+            .enumerate()
+            .filter(|(_, object)| query.is_empty() || object_matches(object, &query))
+            .map(|(index, _)| index)
+            .collect();
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui) {
+        self.poll_load(ui);
+
+        ui.horizontal(|ui| {
+            if ui.button("Open File").clicked() {
+                if let Some(file) = FileDialog::new()
+                    .add_filter("HKX/XML files", &["hkx", "xml"])
+                    .pick_file()
+                {
+                    self.open(file);
+                }
+            }
+            if let Some(path) = &self.path {
+                ui.label(path.to_string_lossy());
+            }
+        });
+
+        if self.load_rx.is_some() {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label("Reading file through serde_hkx...");
+            });
+            return;
+        }
+
+        if let Some(error) = &self.error {
+            ui.label(
+                RichText::new(error)
+                    .color(Color32::from_rgb(255, 120, 120))
+                    .strong(),
+            );
+            return;
+        }
+
+        let Some(document) = &self.document else {
+            ui.label(
+                RichText::new("Open or drop an LE, SE or XML file to inspect its objects")
+                    .color(Color32::from_rgb(100, 100, 100)),
+            );
+            return;
+        };
+
+        ui.horizontal(|ui| {
+            if let Some(contents_version) = &document.contents_version {
+                ui.label(format!("Contents: {}", contents_version));
+            }
+            ui.label(format!("{} objects", document.objects.len()));
+            if let Some(top_level_object) = &document.top_level_object {
+                if ui
+                    .link(format!("Top level: {}", top_level_object))
+                    .clicked()
+                {
+                    self.jump_to = Some(top_level_object.clone());
+                }
+            }
+        });
+
+        let search_changed = ui
+            .horizontal(|ui| {
+                ui.selectable_value(&mut self.view, View::Tree, "Tree");
+                ui.selectable_value(&mut self.view, View::List, "All Objects");
+                ui.separator();
+                ui.label("Search:");
+                let changed = ui.text_edit_singleline(&mut self.search).changed();
+                if !self.search.is_empty() {
+                    ui.label(format!("{} matching objects", self.filtered.len()));
+                }
+                changed
+            })
+            .inner;
+        if search_changed {
+            self.update_filter();
+            if !self.search.is_empty() {
+                self.view = View::List;
+            }
+        }
+
+        // Jumps go to the object list, where every object has exactly one place; make sure
+        // the target is not hidden by the search filter
+        if let Some(target) = &self.jump_to {
+            self.view = View::List;
+            let document = self.document.as_ref().unwrap();
+            let visible = self
+                .filtered
+                .iter()
+                .any(|&index| document.objects[index].name.as_deref() == Some(target));
+            if !visible {
+                self.search.clear();
+                self.update_filter();
+            }
+        }
+
+        ui.separator();
+
+        let document = self.document.as_ref().unwrap();
+        let jump_to = self.jump_to.take();
+        let mut clicked_reference = None;
+
+        if self.view == View::Tree {
+            egui::ScrollArea::vertical()
+                .auto_shrink([false; 2])
+                .show(ui, |ui| {
+                    match &document.top_level_object {
+                        Some(root) => render_tree_node(
+                            ui,
+                            document,
+                            root,
+                            &mut Vec::new(),
+                            &mut clicked_reference,
+                        ),
+                        None => {
+                            ui.label(
+                                RichText::new("The file names no top level object")
+                                    .color(Color32::from_rgb(100, 100, 100)),
+                            );
+                        }
+                    }
+                    if !self.unreachable.is_empty() {
+                        egui::CollapsingHeader::new(format!(
+                            "Not reachable from the top level ({})",
+                            self.unreachable.len()
+                        ))
+                        .id_salt("inspector_unreachable")
+                        .show(ui, |ui| {
+                            for &index in &self.unreachable {
+                                let object = &document.objects[index];
+                                egui::CollapsingHeader::new(
+                                    RichText::new(object.label()).monospace(),
+                                )
+                                .id_salt(("inspector_object", index))
+                                .show(ui, |ui| {
+                                    render_object_body(
+                                        ui,
+                                        document,
+                                        object,
+                                        &mut clicked_reference,
+                                    );
+                                });
+                            }
+                        });
+                    }
+                });
+            if clicked_reference.is_some() {
+                self.jump_to = clicked_reference;
+            }
+            return;
+        }
+
+        egui::ScrollArea::vertical()
+            .auto_shrink([false; 2])
+            .show(ui, |ui| {
+                for &index in &self.filtered {
+                    let object = &document.objects[index];
+                    let is_target = object.name.is_some() && object.name == jump_to;
+
+                    let response =
+                        egui::CollapsingHeader::new(RichText::new(object.label()).monospace())
+                            .id_salt(("inspector_object", index))
+                            .open(is_target.then_some(true))
+                            .show(ui, |ui| {
+                                render_object_body(ui, document, object, &mut clicked_reference);
+                            });
+
+                    if is_target {
+                        response
+                            .header_response
+                            .scroll_to_me(Some(egui::Align::TOP));
+                    }
+                }
+            });
+
+        if clicked_reference.is_some() {
+            self.jump_to = clicked_reference;
+        }
+    }
+}
+
+/// An object with the objects it points to nested below it. An object already open further
+/// up the same branch is shown as a link instead of being expanded again.
+fn render_tree_node(
+    ui: &mut Ui,
+    document: &HkxDocument,
+    name: &str,
+    ancestors: &mut Vec<String>,
+    clicked_reference: &mut Option<String>,
+) {
+    let Some(object) = document.object(name) else {
+        ui.label(
+            RichText::new(format!("{} (missing)", name)).color(Color32::from_rgb(255, 120, 120)),
+        );
+        return;
+    };
+    if ancestors.iter().any(|ancestor| ancestor == name) {
+        if ui
+            .link(RichText::new(format!("↻ {}", object.label())).monospace())
+            .on_hover_text("Already open above; show it in the object list")
+            .clicked()
+        {
+            *clicked_reference = Some(name.to_string());
+        }
+        return;
+    }
+
+    ancestors.push(name.to_string());
+    egui::CollapsingHeader::new(RichText::new(object.label()).monospace())
+        .id_salt(("inspector_node", name))
+        .default_open(ancestors.len() == 1)
+        .show(ui, |ui| {
+            render_object_body(ui, document, object, clicked_reference);
+            let mut children = Vec::new();
+            for child in object.references() {
+                if !children.contains(&child) {
+                    children.push(child);
+                }
+            }
+            for child in children {
+                render_tree_node(ui, document, child, ancestors, clicked_reference);
+            }
+        });
+    ancestors.pop();
+}
+
+fn render_object_body(
+    ui: &mut Ui,
+    document: &HkxDocument,
+    object: &HkxObject,
+    clicked_reference: &mut Option<String>,
+) {
+    ui.horizontal(|ui| {
+        if ui.small_button("Copy as XML").clicked() {
+            ui.ctx().copy_text(object.to_xml());
+        }
+        if let Some(name) = &object.name {
+            if ui
+                .small_button("Copy with references")
+                .on_hover_text("Copy this object and every object it points to")
+                .clicked()
+            {
+                ui.ctx().copy_text(reachable_xml(document, name));
+            }
+        }
+        if let Some(signature) = &object.signature {
+            ui.label(RichText::new(format!("signature {}", signature)).weak());
+        }
+    });
+
+    for (param_index, param) in object.params.iter().enumerate() {
+        match &param.value {
+            HkxValue::Text(text) => {
+                ui.horizontal_wrapped(|ui| {
+                    ui.label(RichText::new(format!("{}:", param.name)).strong());
+                    if hkx_document::pointer_references(text).next().is_some() {
+                        for token in text.split_whitespace() {
+                            match document.object(token) {
+                                Some(target) => {
+                                    let class = target.class.as_deref().unwrap_or_default();
+                                    if ui.link(token).on_hover_text(class).clicked() {
+                                        *clicked_reference = Some(token.to_string());
+                                    }
+                                }
+                                None => {
+                                    ui.label(token);
+                                }
+                            }
+                        }
+                    } else {
+                        ui.label(text.trim());
+                    }
+                });
+            }
+            HkxValue::Strings(strings) => {
+                egui::CollapsingHeader::new(format!("{} [{}]", param.name, strings.len()))
+                    .id_salt(("inspector_param", param_index))
+                    .show(ui, |ui| {
+                        for (string_index, string) in strings.iter().enumerate() {
+                            ui.label(format!("[{}] {}", string_index, string));
+                        }
+                    });
+            }
+            HkxValue::Objects(members) => {
+                egui::CollapsingHeader::new(format!("{} [{}]", param.name, members.len()))
+                    .id_salt(("inspector_param", param_index))
+                    .show(ui, |ui| {
+                        for (member_index, member) in members.iter().enumerate() {
+                            egui::CollapsingHeader::new(format!(
+                                "[{}] {}",
+                                member_index,
+                                member.class.as_deref().unwrap_or_default()
+                            ))
+                            .id_salt(("inspector_member", member_index))
+                            .show(ui, |ui| {
+                                render_object_body(ui, document, member, clicked_reference);
+                            });
+                        }
+                    });
+            }
+        }
+    }
+}
+
+/// Whether an object's name, class or any parameter contains `query` (already lowercased)
+fn object_matches(object: &HkxObject, query: &str) -> bool {
+    object.label().to_lowercase().contains(query)
+        || object.params.iter().any(|param| {
+            param.name.to_lowercase().contains(query)
+                || match &param.value {
+                    HkxValue::Text(text) => text.to_lowercase().contains(query),
+                    HkxValue::Strings(strings) => strings
+                        .iter()
+                        .any(|string| string.to_lowercase().contains(query)),
+                    HkxValue::Objects(members) => {
+                        members.iter().any(|member| object_matches(member, query))
+                    }
+                }
+        })
+}
+
+/// Names of an object and everything reachable from it through pointers
+fn reachable(document: &HkxDocument, root: &str) -> HashSet<String> {
+    let mut visited = HashSet::new();
+    let mut stack = vec![root.to_string()];
+    while let Some(name) = stack.pop() {
+        let Some(object) = document.object(&name) else {
+            continue;
+        };
+        if !visited.insert(name) {
+            continue;
+        }
+        stack.extend(object.references().into_iter().map(str::to_string));
+    }
+    visited
+}
+
+/// XML of an object and everything reachable from it through pointers, in file order
+fn reachable_xml(document: &HkxDocument, root: &str) -> String {
+    let visited = reachable(document, root);
+    document
+        .objects
+        .iter()
+        .filter(|object| {
+            object
+                .name
+                .as_ref()
+                .is_some_and(|name| visited.contains(name))
+        })
+        .map(HkxObject::to_xml)
+        .collect()
+}
@@ -2,19 +2,70 @@ use anyhow::{Context as AnyhowContext, Result};
 use eframe::egui::StrokeKind;
 use eframe::{egui, Frame};
 use egui::{Color32, Context as EguiContext, RichText, Ui};
+use egui_extras::{Column, TableBuilder};
 use futures::future::join_all;
 use rfd::FileDialog;
+use std::collections::{HashMap, HashSet};
 use std::ffi::OsStr;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
 use tempfile;
 use tokio::process::Command;
 use tokio::sync::{mpsc, oneshot};
 use walkdir;
 
+mod animation_report;
+mod annotations;
+mod annotations_tab;
+mod bsa;
+mod bsa_browser;
+mod class_check;
+mod file_kind;
+mod file_table;
+mod hct;
+mod hkx_document;
+mod in_place;
+mod input_filter;
+mod inspector;
+mod mo2;
+mod nemesis;
+mod nemesis_tab;
+mod packfile;
+mod project;
+mod report_tab;
+mod settings;
+mod skeletons;
+mod tool_options;
+mod tool_run;
+mod verify;
+mod xml;
+
+use annotations_tab::AnnotationsTab;
+use bsa::{BsaArchive, BsaVersion, PackOptions};
+use bsa_browser::BsaBrowser;
+use class_check::ClassReport;
+use file_kind::{FileKind, KindDetector};
+use file_table::{FileColumn, FileRow, FileState, FileUpdate, TableLine};
+use hct::{
+    CustomHko, HctDiagnostic, HctFailure, HctLookup, HctSettings, HkoOptions, PlatformPreset,
+};
+use hkx_document::HkxDocument;
+use in_place::ReplacedFile;
+use input_filter::{FolderPreview, InputFilter};
+use inspector::Inspector;
+use mo2::Mo2Instance;
+use nemesis_tab::NemesisTab;
+use project::{ProjectFileKind, ProjectScan};
+use report_tab::ReportTab;
+use settings::Settings;
+use skeletons::{SkeletonInfo, SkeletonMap, SkeletonValidator};
+use tool_options::{DebugLevel, HavokPlatform, HkxConvFormat, ToolOptions};
+use tool_run::{ToolFailure, ToolRun};
+
 const HKXCMD_EXE: &[u8] = include_bytes!("hkxcmd.exe");
 const HKXCONV_EXE: &[u8] = include_bytes!("hkxconv.exe");
-const SSE_TO_LE_HKO: &[u8] = include_bytes!("_SSEtoLE.hko");
 const HAVOK_BEHAVIOR_POST_PROCESS_EXE: &[u8] = include_bytes!("HavokBehaviorPostProcess.exe");
 
 #[derive(PartialEq, Clone, Copy, Debug)]
@@ -27,6 +78,14 @@ enum ConverterTool {
 }
 
 impl ConverterTool {
+    const ALL: [Self; 5] = [
+        ConverterTool::HkxCmd,
+        ConverterTool::HkxC,
+        ConverterTool::HkxConv,
+        ConverterTool::Hct,
+        ConverterTool::HavokBehaviorPostProcess,
+    ];
+
     fn tool_name(&self) -> &'static str {
         match self {
             ConverterTool::HkxCmd => "hkxcmd",
@@ -99,7 +158,11 @@ impl ConverterTool {
                 OutputFormat::SkyrimSE,
             ],
             ConverterTool::HkxConv => &[OutputFormat::Xml, OutputFormat::SkyrimSE],
-            ConverterTool::Hct => &[OutputFormat::SkyrimLE],
+            ConverterTool::Hct => &[
+                OutputFormat::SkyrimLE,
+                OutputFormat::SkyrimSE,
+                OutputFormat::Xml,
+            ],
             ConverterTool::HavokBehaviorPostProcess => &[OutputFormat::SkyrimSE],
         }
     }
@@ -119,6 +182,29 @@ impl ConverterTool {
     }
 }
 
+#[derive(PartialEq, Clone, Copy, Debug)]
+enum AppTab {
+    Converter,
+    Inspector,
+    Annotations,
+    AnimationReport,
+    NemesisPatch,
+    Settings,
+}
+
+impl AppTab {
+    fn label(&self) -> &'static str {
+        match self {
+            AppTab::Converter => "Converter",
+            AppTab::Inspector => "Inspector",
+            AppTab::Annotations => "Annotations",
+            AppTab::AnimationReport => "Animation Report",
+            AppTab::NemesisPatch => "Nemesis Patch",
+            AppTab::Settings => "Settings",
+        }
+    }
+}
+
 #[derive(PartialEq, Clone, Copy, Debug)]
 enum ConversionMode {
     /// HKX <-> XML
@@ -151,6 +237,18 @@ struct ConversionProgress {
     file_index: usize,
     total_files: usize,
     status: ConversionStatus,
+    diagnostics: Option<FileDiagnostics>,
+    /// Set when an in-place conversion replaced the original
+    replaced: Option<ReplacedFile>,
+    file: Option<FileUpdate>,
+}
+
+/// Warnings and errors logged by HCT for one file
+#[derive(Debug, Clone)]
+struct FileDiagnostics {
+    diagnostics: Vec<HctDiagnostic>,
+    /// HCT's temporary directory, kept when the conversion failed
+    kept_temp_dir: Option<PathBuf>,
 }
 
 impl ConversionMode {
@@ -193,9 +291,55 @@ impl InputFileExtension {
 }
 
 struct HkxToolsApp {
+    active_tab: AppTab,
+    inspector: Inspector,
+    annotations_tab: AnnotationsTab,
+    report_tab: ReportTab,
+    nemesis_tab: NemesisTab,
+    settings: Settings,
+    hct_lookup: HctLookup,
+    /// Files to convert; paths continuing past a `.bsa` file are archive entries
     input_paths: Vec<PathBuf>,
+    /// Folder each input was added from; the input patterns are matched below it
+    input_roots: HashMap<PathBuf, PathBuf>,
+    bsa_browser: BsaBrowser,
+    /// Behavior projects whose files were queued; they pick the tool and output subfolder
+    /// for those files
+    projects: Vec<ProjectScan>,
+    project_load: Option<oneshot::Receiver<Result<ProjectScan>>>,
+    project_error: Option<String>,
+    mo2: Option<Mo2Instance>,
+    mo2_error: Option<String>,
+    mo2_input_mod: Option<String>,
+    /// Mod the outputs go to, mirroring the inputs' data layout; overrides `output_folder`
+    mo2_output_mod: Option<String>,
+    mo2_new_mod: String,
     output_folder: Option<PathBuf>,
+    /// Default skeleton for KF files without a detected or overridden one
     skeleton_file: Option<PathBuf>,
+    skeletons: SkeletonMap,
+    skeleton_validator: SkeletonValidator,
+    kind_detector: KindDetector,
+    /// Only list inputs of this kind; `None` lists every file
+    kind_filter: Option<FileKind>,
+    /// Column the file table is sorted by, and whether ascending
+    file_sort: Option<(FileColumn, bool)>,
+    /// Only list inputs whose name or folder contains this
+    file_search: String,
+    /// List inputs under a header per folder
+    group_by_folder: bool,
+    collapsed_folders: HashSet<String>,
+    /// Inputs selected in the file table, and the one a shift-click selects from
+    selected_files: HashSet<PathBuf>,
+    selection_anchor: Option<PathBuf>,
+    /// Failed input whose tool output is shown
+    failure_detail: Option<PathBuf>,
+    /// Saved tool option presets, and the name typed for saving one
+    tool_presets: Vec<String>,
+    preset_name: String,
+    /// Folder added last and whether with subfolders, for previewing the input patterns
+    last_input_folder: Option<(PathBuf, bool)>,
+    input_preview: FolderPreview,
     output_suffix: String,
     output_format: OutputFormat,
     custom_extension: Option<String>,
@@ -204,10 +348,25 @@ struct HkxToolsApp {
     conversion_mode: ConversionMode,
     hkxcmd_path: PathBuf,
     hkxconv_path: PathBuf,
-    sse_to_le_hko_path: PathBuf,
     havok_behavior_post_process_path: PathBuf,
+    hct_settings: HctSettings,
+    hct_custom_hko: Option<CustomHko>,
+    hct_use_custom_hko: bool,
+    /// Replace the input files instead of writing to the output folder
+    in_place: bool,
+    /// Pack the outputs into `<output folder>/<bsa_name>.bsa` instead of writing loose files
+    pack_bsa: bool,
+    bsa_name: String,
+    bsa_version: BsaVersion,
+    bsa_compress: bool,
+    confirm_in_place: bool,
+    /// Originals replaced by the last in-place conversion, for undo
+    replaced_files: Vec<ReplacedFile>,
     // Async operation fields
     conversion_status: ConversionStatus,
+    file_diagnostics: Vec<(String, FileDiagnostics)>,
+    /// State of each input in the last run
+    file_updates: HashMap<PathBuf, FileUpdate>,
     progress_rx: Option<mpsc::UnboundedReceiver<ConversionProgress>>,
     cancel_tx: Option<oneshot::Sender<()>>,
     tokio_handle: tokio::runtime::Handle,
@@ -258,9 +417,41 @@ impl TryFrom<OutputFormat> for serde_hkx_features::OutFormat {
 impl Default for HkxToolsApp {
     fn default() -> Self {
         Self {
+            active_tab: AppTab::Converter,
+            inspector: Inspector::new(tokio::runtime::Handle::current()),
+            annotations_tab: AnnotationsTab::new(tokio::runtime::Handle::current(), PathBuf::new()),
+            report_tab: ReportTab::new(tokio::runtime::Handle::current()),
+            nemesis_tab: NemesisTab::new(tokio::runtime::Handle::current()),
+            settings: Settings::default(),
+            hct_lookup: HctLookup::locate(None),
             input_paths: Vec::new(),
+            input_roots: HashMap::new(),
+            bsa_browser: BsaBrowser::default(),
+            projects: Vec::new(),
+            project_load: None,
+            project_error: None,
+            mo2: None,
+            mo2_error: None,
+            mo2_input_mod: None,
+            mo2_output_mod: None,
+            mo2_new_mod: String::new(),
             output_folder: None,
             skeleton_file: None,
+            skeletons: SkeletonMap::default(),
+            skeleton_validator: SkeletonValidator::new(tokio::runtime::Handle::current()),
+            kind_detector: KindDetector::new(tokio::runtime::Handle::current()),
+            kind_filter: None,
+            file_sort: None,
+            file_search: String::new(),
+            group_by_folder: false,
+            collapsed_folders: HashSet::new(),
+            selected_files: HashSet::new(),
+            selection_anchor: None,
+            failure_detail: None,
+            tool_presets: settings::preset_names(),
+            preset_name: String::new(),
+            last_input_folder: None,
+            input_preview: FolderPreview::default(),
             output_suffix: String::new(),
             output_format: OutputFormat::Xml,
             custom_extension: None,
@@ -269,9 +460,20 @@ impl Default for HkxToolsApp {
             conversion_mode: ConversionMode::Regular,
             hkxcmd_path: PathBuf::new(),
             hkxconv_path: PathBuf::new(),
-            sse_to_le_hko_path: PathBuf::new(),
             havok_behavior_post_process_path: PathBuf::new(),
+            hct_settings: HctSettings::default(),
+            hct_custom_hko: None,
+            hct_use_custom_hko: false,
+            in_place: false,
+            pack_bsa: false,
+            bsa_name: "Converted".to_string(),
+            bsa_version: BsaVersion::Se,
+            bsa_compress: true,
+            confirm_in_place: false,
+            replaced_files: in_place::load_pending(),
             conversion_status: ConversionStatus::Idle,
+            file_diagnostics: Vec::new(),
+            file_updates: HashMap::new(),
             progress_rx: None,
             cancel_tx: None,
             tokio_handle: tokio::runtime::Handle::current(),
@@ -279,6 +481,15 @@ impl Default for HkxToolsApp {
     }
 }
 
+/// What a converter left behind for one file besides its output
+struct ToolOutput {
+    diagnostics: Vec<HctDiagnostic>,
+    /// HCT's working folder, removed once the output has been verified
+    hct_temp_dir: Option<tempfile::TempDir>,
+    /// How the output was produced, shown if it fails verification
+    run: Option<ToolRun>,
+}
+
 // Temporary context for async conversion operations
 struct TempConversionContext {
     converter_tool: ConverterTool,
@@ -287,17 +498,35 @@ struct TempConversionContext {
     skeleton_file: Option<PathBuf>,
     hkxcmd_path: PathBuf,
     hkxconv_path: PathBuf,
-    sse_to_le_hko_path: PathBuf,
     havok_behavior_post_process_path: PathBuf,
+    hct_path: PathBuf,
+    hct_options: HkoOptions,
+    tool_options: ToolOptions,
 }
 
 impl TempConversionContext {
-    async fn run_conversion_tool(&self, input: &Path, output: &Path) -> Result<()> {
+    /// Format this file's tool really writes: HCT follows its options' platform writer and
+    /// the other tools their typed options, whatever output format is selected
+    fn produced_format(&self) -> OutputFormat {
+        match (self.conversion_mode, self.converter_tool) {
+            (ConversionMode::Regular, ConverterTool::Hct) => self
+                .hct_options
+                .active()
+                .and_then(|configuration| configuration.output_format())
+                .unwrap_or(self.output_format),
+            (mode, tool) => self
+                .tool_options
+                .produced_format(tool, mode, self.output_format),
+        }
+    }
+
+    /// Run the selected tool on one file, returning any HCT warnings it logged
+    async fn run_conversion_tool(&self, input: &Path, output: &Path) -> Result<ToolOutput> {
         let mut command = match self.converter_tool {
             ConverterTool::HkxCmd => Command::new(&self.hkxcmd_path),
             ConverterTool::HkxC => Command::new(&"cmd.exe"), // dummy to same type
             ConverterTool::HkxConv => Command::new(&self.hkxconv_path),
-            ConverterTool::Hct => Command::new("hctStandAloneFilterManager.exe"),
+            ConverterTool::Hct => Command::new(&self.hct_path),
             ConverterTool::HavokBehaviorPostProcess => {
                 Command::new(&self.havok_behavior_post_process_path)
             }
@@ -340,33 +569,65 @@ impl TempConversionContext {
             }
         }
 
+        // User options go before the file arguments
+        let extra_args = self
+            .tool_options
+            .args(self.converter_tool, self.conversion_mode);
+
         // Add arguments based on conversion mode and tool
         match (self.conversion_mode, self.converter_tool) {
             (ConversionMode::Regular, ConverterTool::HkxCmd) => {
+                command.args(&extra_args);
                 command.arg("-i").arg(&input_absolute);
                 command.arg("-o").arg(&output_absolute);
                 command.arg(format!(
                     "-v:{}",
-                    match self.output_format {
-                        OutputFormat::Xml => "XML",
-                        OutputFormat::SkyrimLE => "WIN32",
-                        OutputFormat::SkyrimSE => "AMD64",
-                        OutputFormat::Kf => "AMD64", // KF output format should not be used in regular conversion
-                    }
+                    self.tool_options
+                        .hkxcmd_platform(self.output_format)
+                        .as_str()
                 ));
             }
             (ConversionMode::Regular, ConverterTool::HkxC) => {
                 // NOTE: KF output format should not be used in regular conversion
-                serde_hkx_features::convert::tokio::convert(
+                let format = self
+                    .output_format
+                    .try_into()
+                    .unwrap_or(serde_hkx_features::OutFormat::Amd64);
+                let result = serde_hkx_features::convert::tokio::convert(
                     &input_absolute,
                     Some(&output_absolute),
-                    self.output_format
-                        .try_into()
-                        .unwrap_or(serde_hkx_features::OutFormat::Amd64),
+                    format,
                 )
-                .await?
+                .await;
+                // serde_hkx runs in this process, so its call stands in for a command line
+                let run = ToolRun::in_process(
+                    "serde_hkx convert",
+                    &[
+                        &input_absolute.to_string_lossy(),
+                        &output_absolute.to_string_lossy(),
+                        match self.output_format {
+                            OutputFormat::Xml => "xml",
+                            OutputFormat::SkyrimLE => "win32",
+                            _ => "amd64",
+                        },
+                    ],
+                    result.as_ref().err().map(|e| e.to_string()),
+                );
+                if let Err(e) = result {
+                    return Err(ToolFailure {
+                        message: format!("{} failed: {}", tool_name, e),
+                        run,
+                    }
+                    .into());
+                }
+                return Ok(ToolOutput {
+                    diagnostics: Vec::new(),
+                    hct_temp_dir: None,
+                    run: Some(run),
+                });
             }
             (ConversionMode::KfToHkx, ConverterTool::HkxCmd) => {
+                command.args(&extra_args);
                 if let Some(skeleton) = &skeleton_absolute {
                     command.arg(skeleton);
                 }
@@ -374,15 +635,13 @@ impl TempConversionContext {
                 command.arg(&output_absolute);
                 command.arg(format!(
                     "-v:{}",
-                    match self.output_format {
-                        OutputFormat::Xml => "XML",
-                        OutputFormat::SkyrimLE => "WIN32",
-                        OutputFormat::SkyrimSE => "AMD64",
-                        OutputFormat::Kf => "AMD64", // KF output format should not be used in KfToHkx conversion
-                    }
+                    self.tool_options
+                        .hkxcmd_platform(self.output_format)
+                        .as_str()
                 ));
             }
             (ConversionMode::HkxToKf, ConverterTool::HkxCmd) => {
+                command.args(&extra_args);
                 if let Some(skeleton) = &skeleton_absolute {
                     command.arg(skeleton);
                 }
@@ -397,14 +656,14 @@ impl TempConversionContext {
             }
             (ConversionMode::Regular, ConverterTool::HkxConv) => {
                 command.arg("convert");
+                command.args(&extra_args);
                 command.arg(&input_absolute);
                 command.arg(&output_absolute);
-                command.arg("-v").arg(match self.output_format {
-                    OutputFormat::Xml => "xml",
-                    OutputFormat::SkyrimLE => "hkx",
-                    OutputFormat::SkyrimSE => "hkx",
-                    OutputFormat::Kf => "hkx", // KF output format should not be used in regular conversion
-                });
+                command.arg("-v").arg(
+                    self.tool_options
+                        .hkxconv_format(self.output_format)
+                        .as_str(),
+                );
             }
             (ConversionMode::KfToHkx, ConverterTool::HkxConv) => {
                 return Err(anyhow::anyhow!("hkxconv does not support KF conversion"));
@@ -413,26 +672,25 @@ impl TempConversionContext {
                 return Err(anyhow::anyhow!("hkxconv does not support KF conversion"));
             }
             (ConversionMode::Regular, ConverterTool::Hct) => {
+                // HCT writes the "Write to Platform" filename next to the .hko file; without
+                // one there is nothing to collect, so don't run it at all
+                let hct_output_filename = self
+                    .hct_options
+                    .active()
+                    .and_then(|configuration| configuration.output_filename())
+                    .context("HCT configuration has no \"Write to Platform\" filter")?;
+
                 // For HCT, create a unique temporary directory for this conversion
                 let temp_dir = tempfile::Builder::new()
                     .prefix("hct_conversion_")
                     .tempdir()
                     .context("Failed to create temporary directory for HCT conversion")?;
 
-                // HCT only supports SSE to LE conversion
-                let source_hko_path = &self.sse_to_le_hko_path;
-
-                // Copy the .hko file to the temporary directory
-                let hko_filename = source_hko_path.file_name().unwrap();
+                // Write the .hko option file for this batch into the temporary directory
+                let hko_filename = "options.hko";
                 let temp_hko_path = temp_dir.path().join(hko_filename);
-                fs::copy(source_hko_path, &temp_hko_path)
-                    .context("Failed to copy .hko file to temporary directory")?;
-
-                println!(
-                    "HCT temp dir: {:?}, using .hko: {:?}",
-                    temp_dir.path(),
-                    hko_filename
-                );
+                fs::write(&temp_hko_path, self.hct_options.to_hko_string())
+                    .context("Failed to write .hko file to temporary directory")?;
 
                 // Set working directory to temp directory and use relative .hko filename
                 command.current_dir(temp_dir.path());
@@ -440,80 +698,84 @@ impl TempConversionContext {
                 command.arg("-s");
                 command.arg(hko_filename); // Just the filename, not full path
 
-                // Execute the command
+                // Execute the command, capturing the filter manager's log
                 let cmd_output = command
                     .output()
                     .await
                     .context("Failed to execute HCT converter tool")?;
+                let run = ToolRun::new(&command, &cmd_output);
+                let stdout = String::from_utf8_lossy(&cmd_output.stdout);
                 let stderr = String::from_utf8_lossy(&cmd_output.stderr);
+                let mut diagnostics = hct::parse_filter_log(
+                    &format!("{}\n{}", stdout, stderr),
+                    &self.hct_options.filter_names(),
+                );
 
-                if !cmd_output.status.success() {
-                    return Err(anyhow::anyhow!("{} failed: {}", tool_name, stderr));
-                }
-
-                // HCT creates "filename.hkx" in the same directory as the .hko file
-                let hct_output_file = temp_dir.path().join("filename.hkx");
-
-                // Debug: List all files in temp directory
-                println!("Temp directory contents:");
-                if let Ok(entries) = fs::read_dir(temp_dir.path()) {
-                    for entry in entries.flatten() {
-                        println!("  {:?}", entry.path());
-                    }
+                let hct_output_file = temp_dir.path().join(&hct_output_filename);
+
+                let failure = if !cmd_output.status.success() {
+                    Some(format!(
+                        "{} failed with exit code {:?}",
+                        tool_name,
+                        cmd_output.status.code()
+                    ))
+                } else if !hct_output_file.exists() {
+                    Some(format!(
+                        "HCT did not produce expected output file {:?}",
+                        hct_output_filename
+                    ))
                 } else {
-                    println!("  Failed to read temp directory");
-                }
-
-                if !hct_output_file.exists() {
-                    return Err(anyhow::anyhow!(
-                        "HCT did not produce expected output file: {:?}",
-                        hct_output_file
-                    ));
+                    None
+                };
+
+                if let Some(message) = failure {
+                    // Nothing recognisable in the log, so pass on whatever HCT printed
+                    if diagnostics.is_empty() {
+                        let output = format!("{}\n{}", stdout.trim(), stderr.trim());
+                        if !output.trim().is_empty() {
+                            diagnostics.push(HctDiagnostic {
+                                filter: None,
+                                severity: hct::Severity::Error,
+                                message: output.trim().to_string(),
+                            });
+                        }
+                    }
+                    return Err(HctFailure {
+                        message,
+                        diagnostics,
+                        temp_dir: temp_dir.keep(),
+                        run,
+                    }
+                    .into());
                 }
 
-                println!("HCT output file exists: {:?}", hct_output_file);
-                println!("Target output path: {:?}", output_absolute);
-
                 // Create output directory if it doesn't exist
                 if let Some(parent) = output_absolute.parent() {
-                    println!("Creating output directory: {:?}", parent);
                     fs::create_dir_all(parent).context("Failed to create output directory")?;
                 }
 
                 // Check if target file already exists and remove it if necessary
                 if output_absolute.exists() {
-                    println!(
-                        "Target file already exists, removing: {:?}",
-                        output_absolute
-                    );
                     fs::remove_file(&output_absolute)
                         .context("Failed to remove existing target file")?;
                 }
 
                 // Move the HCT output file directly to the final location
                 // The output_absolute path already includes any suffix/extension modifications
-                match fs::rename(&hct_output_file, &output_absolute) {
-                    Ok(_) => {
-                        println!("Successfully moved HCT output to: {:?}", output_absolute);
-                    }
-                    Err(e) => {
-                        // If rename fails, try copy + delete as fallback
-                        println!("Rename failed ({}), trying copy + delete fallback", e);
-                        fs::copy(&hct_output_file, &output_absolute)
-                            .context("Failed to copy HCT output file to final location")?;
-                        fs::remove_file(&hct_output_file)
-                            .context("Failed to remove temporary HCT output file after copy")?;
-                        println!("Successfully copied HCT output to: {:?}", output_absolute);
-                    }
+                if fs::rename(&hct_output_file, &output_absolute).is_err() {
+                    // If rename fails, try copy + delete as fallback
+                    fs::copy(&hct_output_file, &output_absolute)
+                        .context("Failed to copy HCT output file to final location")?;
+                    fs::remove_file(&hct_output_file)
+                        .context("Failed to remove temporary HCT output file after copy")?;
                 }
 
-                println!(
-                    "HCT conversion complete: {:?} -> {:?}",
-                    input_absolute, output_absolute
-                );
-
-                // temp_dir will be automatically cleaned up when it goes out of scope
-                return Ok(());
+                // The working folder stays until the output has been verified
+                return Ok(ToolOutput {
+                    diagnostics,
+                    hct_temp_dir: Some(temp_dir),
+                    run: Some(run),
+                });
             }
             (ConversionMode::KfToHkx, ConverterTool::Hct) => {
                 return Err(anyhow::anyhow!("HCT does not support KF conversion"));
@@ -529,19 +791,6 @@ impl TempConversionContext {
                     ));
                 }
 
-                // HavokBehaviorPostProcess modifies files in-place, so we need to copy the input to output first
-                println!("Input path: {:?}", input_absolute);
-                println!("Output path: {:?}", output_absolute);
-                println!("Input exists: {}", input_absolute.exists());
-                println!(
-                    "Output parent exists: {}",
-                    output_absolute.parent().map_or(false, |p| p.exists())
-                );
-                println!(
-                    "Copying input file to output location: {:?} -> {:?}",
-                    input_absolute, output_absolute
-                );
-
                 // Check if input and output are the same
                 if input_absolute == output_absolute {
                     return Err(anyhow::anyhow!(
@@ -550,37 +799,26 @@ impl TempConversionContext {
                     ));
                 }
 
+                let input_bytes = fs::read(&input_absolute).context("Failed to read input file")?;
+                let input_header = packfile::PackfileHeader::parse(&input_bytes)?;
+                if input_header.pointer_size != 4 {
+                    return Err(anyhow::anyhow!(
+                        "HavokBehaviorPostProcess converts 32-bit (LE) packfiles, but input is a {} packfile",
+                        input_header.describe()
+                    ));
+                }
+
                 // Create output directory if it doesn't exist
                 if let Some(parent) = output_absolute.parent() {
-                    println!("Creating output directory: {:?}", parent);
                     fs::create_dir_all(parent).context("Failed to create output directory")?;
                 }
 
-                // Copy input file to output location
-                match fs::copy(&input_absolute, &output_absolute) {
-                    Ok(bytes_copied) => {
-                        println!("Successfully copied {} bytes", bytes_copied);
-                    }
-                    Err(e) => {
-                        println!("Copy failed with error: {:?}", e);
-                        return Err(anyhow::anyhow!(
-                            "Failed to copy input file to output location: {}",
-                            e
-                        ));
-                    }
-                }
-
-                // Check file size before processing
-                let file_size_before = fs::metadata(&output_absolute)
-                    .context("Failed to get file metadata before processing")?
-                    .len();
-                println!(
-                    "File size before HavokBehaviorPostProcess: {} bytes",
-                    file_size_before
-                );
+                // HavokBehaviorPostProcess modifies files in-place, so we copy the input to output first
+                fs::copy(&input_absolute, &output_absolute)
+                    .context("Failed to copy input file to output location")?;
 
                 // Run HavokBehaviorPostProcess on the output file (modifies in-place)
-                command.arg("--platformAmd64");
+                command.args(&extra_args);
                 // Both input and output are the same file (in-place modification)
                 // Don't manually add quotes - let Command handle it
                 command.arg(&output_absolute);
@@ -626,38 +864,38 @@ impl TempConversionContext {
             println!("HavokBehaviorPostProcess stderr: {}", stderr);
         }
 
+        let run = ToolRun::new(&command, &output);
         if !output.status.success() {
-            return Err(anyhow::anyhow!(
-                "{} failed with exit code {:?}: stdout: {} stderr: {}",
-                tool_name,
-                output.status.code(),
-                stdout,
-                stderr
-            ));
+            return Err(ToolFailure {
+                message: format!(
+                    "{} failed with exit code {:?}",
+                    tool_name,
+                    output.status.code()
+                ),
+                run,
+            }
+            .into());
         }
 
-        // For HavokBehaviorPostProcess, check if the file size changed
         if self.converter_tool == ConverterTool::HavokBehaviorPostProcess {
-            let file_size_after = fs::metadata(&output_absolute)
-                .context("Failed to get file metadata after processing")?
-                .len();
-            println!(
-                "File size after HavokBehaviorPostProcess: {} bytes",
-                file_size_after
-            );
-
-            if file_size_after
-                == fs::metadata(&input_absolute)
-                    .context("Failed to get input file metadata")?
-                    .len()
-            {
-                println!("WARNING: Output file size is the same as input file size - conversion may not have worked");
-            } else {
-                println!("SUCCESS: File size changed, conversion appears to have worked");
+            if let Err(e) = verify::check_post_process_output(
+                &input_absolute,
+                &output_absolute,
+                self.produced_format(),
+            ) {
+                return Err(ToolFailure {
+                    message: format!("{:#}", e),
+                    run,
+                }
+                .into());
             }
         }
 
-        Ok(())
+        Ok(ToolOutput {
+            diagnostics: Vec::new(),
+            hct_temp_dir: None,
+            run: Some(run),
+        })
     }
 }
 
@@ -665,14 +903,51 @@ impl HkxToolsApp {
     fn new(
         hkxcmd_path: PathBuf,
         hkxconv_path: PathBuf,
-        sse_to_le_hko_path: PathBuf,
         havok_behavior_post_process_path: PathBuf,
+        settings: Settings,
         tokio_handle: tokio::runtime::Handle,
     ) -> Self {
+        // The instance may have moved since it was picked; then it simply isn't loaded
+        let mo2 = settings
+            .mo2_ini
+            .as_deref()
+            .and_then(|ini| Mo2Instance::open(ini).ok());
         Self {
+            active_tab: AppTab::Converter,
+            inspector: Inspector::new(tokio_handle.clone()),
+            annotations_tab: AnnotationsTab::new(tokio_handle.clone(), hkxcmd_path.clone()),
+            report_tab: ReportTab::new(tokio_handle.clone()),
+            nemesis_tab: NemesisTab::new(tokio_handle.clone()),
+            hct_lookup: HctLookup::locate(settings.hct_path.as_deref()),
+            settings,
             input_paths: Vec::new(),
+            input_roots: HashMap::new(),
+            bsa_browser: BsaBrowser::default(),
+            projects: Vec::new(),
+            project_load: None,
+            project_error: None,
+            mo2,
+            mo2_error: None,
+            mo2_input_mod: None,
+            mo2_output_mod: None,
+            mo2_new_mod: String::new(),
             output_folder: None,
             skeleton_file: None,
+            skeletons: SkeletonMap::default(),
+            skeleton_validator: SkeletonValidator::new(tokio_handle.clone()),
+            kind_detector: KindDetector::new(tokio_handle.clone()),
+            kind_filter: None,
+            file_sort: None,
+            file_search: String::new(),
+            group_by_folder: false,
+            collapsed_folders: HashSet::new(),
+            selected_files: HashSet::new(),
+            selection_anchor: None,
+            failure_detail: None,
+            tool_presets: settings::preset_names(),
+            preset_name: String::new(),
+            last_input_folder: None,
+            input_preview: FolderPreview::default(),
             output_suffix: String::new(),
             output_format: OutputFormat::Xml,
             custom_extension: None,
@@ -681,9 +956,20 @@ impl HkxToolsApp {
             conversion_mode: ConversionMode::Regular,
             hkxcmd_path,
             hkxconv_path,
-            sse_to_le_hko_path,
             havok_behavior_post_process_path,
+            hct_settings: HctSettings::default(),
+            hct_custom_hko: None,
+            hct_use_custom_hko: false,
+            in_place: false,
+            pack_bsa: false,
+            bsa_name: "Converted".to_string(),
+            bsa_version: BsaVersion::Se,
+            bsa_compress: true,
+            confirm_in_place: false,
+            replaced_files: in_place::load_pending(),
             conversion_status: ConversionStatus::Idle,
+            file_diagnostics: Vec::new(),
+            file_updates: HashMap::new(),
             progress_rx: None,
             cancel_tx: None,
             tokio_handle,
@@ -692,12 +978,21 @@ impl HkxToolsApp {
 
     /// Check if a file matches the current input filter and tool capabilities
     fn file_matches_filter(&self, path: &Path) -> bool {
+        Self::file_matches(self.converter_tool, self.input_file_extension, path)
+    }
+
+    /// [`Self::file_matches_filter`] without borrowing the app, for background work
+    fn file_matches(
+        converter_tool: ConverterTool,
+        input_file_extension: InputFileExtension,
+        path: &Path,
+    ) -> bool {
         if !path.is_file() {
             return false;
         }
 
-        match self.input_file_extension {
-            InputFileExtension::All => self.converter_tool.supports_file(path),
+        match input_file_extension {
+            InputFileExtension::All => converter_tool.supports_file(path),
             InputFileExtension::Hkx => path
                 .extension()
                 .is_some_and(|ext| ext.eq_ignore_ascii_case("hkx")),
@@ -743,6 +1038,8 @@ impl HkxToolsApp {
     }
 
     fn add_files_from_folder(&mut self, folder: &Path, recursive: bool) -> Result<()> {
+        // Remembered for previewing the patterns against it
+        self.last_input_folder = Some((folder.to_path_buf(), recursive));
         if recursive {
             self.add_files_recursive(folder)
         } else {
@@ -752,11 +1049,16 @@ impl HkxToolsApp {
 
     fn add_files_non_recursive(&mut self, folder: &Path) -> Result<()> {
         let entries = fs::read_dir(folder).context("Failed to read directory")?;
+        let filter = self.input_filter();
 
         for entry in entries {
             let entry = entry?;
             let path = entry.path();
-            if self.file_matches_filter(&path) && !self.input_paths.contains(&path) {
+            if self.file_matches_filter(&path)
+                && filter.accepts(&path, Some(folder))
+                && !self.input_paths.contains(&path)
+            {
+                self.input_roots.insert(path.clone(), folder.to_path_buf());
                 self.input_paths.push(path);
             }
         }
@@ -764,25 +1066,127 @@ impl HkxToolsApp {
     }
 
     fn add_files_recursive(&mut self, folder: &Path) -> Result<()> {
+        let filter = self.input_filter();
         for entry in walkdir::WalkDir::new(folder).follow_links(true) {
             let entry = entry?;
             let path = entry.path().to_path_buf();
-            if self.file_matches_filter(&path) && !self.input_paths.contains(&path) {
+            if self.file_matches_filter(&path)
+                && filter.accepts(&path, Some(folder))
+                && !self.input_paths.contains(&path)
+            {
+                self.input_roots.insert(path.clone(), folder.to_path_buf());
                 self.input_paths.push(path);
             }
         }
         Ok(())
     }
 
+    /// Forget selections, roots and results of files no longer in the input list
+    fn prune_input_state(&mut self) {
+        let inputs = self.input_paths.iter().collect::<HashSet<_>>();
+        self.selected_files.retain(|path| inputs.contains(path));
+        self.input_roots.retain(|path, _| inputs.contains(path));
+        self.file_updates.retain(|path, _| inputs.contains(path));
+        if self
+            .selection_anchor
+            .as_ref()
+            .is_some_and(|anchor| !inputs.contains(anchor))
+        {
+            self.selection_anchor = None;
+        }
+        if self
+            .failure_detail
+            .as_ref()
+            .is_some_and(|input| !inputs.contains(input))
+        {
+            self.failure_detail = None;
+        }
+    }
+
+    /// The include/exclude patterns from the settings
+    fn input_filter(&self) -> InputFilter {
+        InputFilter::new(&self.settings.include_globs, &self.settings.exclude_globs)
+    }
+
     fn update_output_folder(&mut self) {
+        if self.mo2_output_mod.is_some() {
+            return;
+        }
         if let Some(input_path) = self.input_paths.first() {
+            // Archive entries default to the archive's folder
+            let input_path = bsa::split_archive_path(input_path)
+                .map_or(input_path.clone(), |(archive, _)| archive);
             self.output_folder = Some(input_path.parent().unwrap_or(Path::new("")).to_path_buf());
         }
     }
 
-    /// Add a single file to the input files list, checking if it matches the current extension filter
+    fn render_bsa_browser(&mut self, ctx: &EguiContext) {
+        let converter_tool = self.converter_tool;
+        let added = self
+            .bsa_browser
+            .show(ctx, |path| converter_tool.supports_file(path));
+        if added.is_empty() {
+            return;
+        }
+        let filter = self.input_filter();
+        for path in added {
+            if filter.accepts(&path, None) && !self.input_paths.contains(&path) {
+                self.input_paths.push(path);
+            }
+        }
+        if self.output_folder.is_none() {
+            self.update_output_folder();
+        }
+    }
+
+    /// Read a behavior project in the background; its files are queued once it is done
+    fn load_project(&mut self, project: PathBuf) {
+        let (tx, rx) = oneshot::channel();
+        self.tokio_handle.spawn(async move {
+            let _ = tx.send(ProjectScan::load(&project).await);
+        });
+        self.project_load = Some(rx);
+        self.project_error = None;
+    }
+
+    fn queue_project(&mut self, scan: ProjectScan) {
+        let filter = self.input_filter();
+        for (path, kind) in &scan.files {
+            let queue = match self.conversion_mode {
+                ConversionMode::Regular => true,
+                ConversionMode::HkxToKf => *kind == ProjectFileKind::Animation,
+                ConversionMode::KfToHkx => false,
+            };
+            if queue
+                && filter.accepts(path, scan.project.parent())
+                && !self.input_paths.contains(path)
+            {
+                if let Some(root) = scan.project.parent() {
+                    self.input_roots.insert(path.clone(), root.to_path_buf());
+                }
+                self.input_paths.push(path.clone());
+            }
+        }
+        // The project's animations all belong to its rig
+        if let (Some(skeleton), Some(root)) = (&scan.skeleton, scan.project.parent()) {
+            self.skeletons
+                .set_override(root.to_path_buf(), skeleton.clone());
+        }
+        self.projects
+            .retain(|existing| existing.project != scan.project);
+        self.projects.push(scan);
+        self.update_output_folder();
+    }
+
     fn add_file(&mut self, file_path: PathBuf) -> bool {
-        if self.file_matches_filter(&file_path) && !self.input_paths.contains(&file_path) {
+        if self.file_matches_filter(&file_path)
+            && self.input_filter().accepts(&file_path, file_path.parent())
+            && !self.input_paths.contains(&file_path)
+        {
+            if let Some(root) = file_path.parent() {
+                self.input_roots
+                    .insert(file_path.clone(), root.to_path_buf());
+            }
             self.input_paths.push(file_path);
             true
         } else {
@@ -797,26 +1201,25 @@ impl HkxToolsApp {
 
         for dropped_file in dropped_files {
             if let Some(path) = dropped_file.path {
-                if path.is_file() {
+                if path
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("bsa"))
+                {
+                    self.bsa_browser.open_archive(&path);
+                } else if path.is_file() {
                     if self.add_file(path) {
                         files_added += 1;
                     } else {
                         files_skipped += 1;
                     }
                 } else if path.is_dir() {
-                    // If a directory is dropped, add all files from it (non-recursive)
-                    if let Ok(entries) = std::fs::read_dir(&path) {
-                        for entry in entries.flatten() {
-                            let entry_path = entry.path();
-                            if entry_path.is_file() {
-                                if self.add_file(entry_path) {
-                                    files_added += 1;
-                                } else {
-                                    files_skipped += 1;
-                                }
-                            }
-                        }
+                    // A dropped directory adds its files like the folder buttons do
+                    let before = self.input_paths.len();
+                    if let Err(e) = self.add_files_from_folder(&path, self.settings.recursive_drop)
+                    {
+                        eprintln!("Error adding dropped folder: {:#}", e);
                     }
+                    files_added += self.input_paths.len() - before;
                 }
             }
         }
@@ -1024,18 +1427,181 @@ impl HkxToolsApp {
             };
             return;
         }
-        if self.output_folder.is_none() {
+        if self.in_place
+            && self
+                .input_paths
+                .iter()
+                .any(|path| bsa::split_archive_path(path).is_some())
+        {
+            self.conversion_status = ConversionStatus::Error {
+                message: "Files inside a BSA archive can't be converted in place".to_string(),
+            };
+            return;
+        }
+        if self.in_place && self.pack_bsa {
+            self.conversion_status = ConversionStatus::Error {
+                message: "Choose either in-place conversion or packing into a BSA".to_string(),
+            };
+            return;
+        }
+        if self.output_folder.is_none() && !self.in_place {
             self.conversion_status = ConversionStatus::Error {
                 message: "No output folder selected".to_string(),
             };
             return;
         }
-        if self.conversion_mode.requires_skeleton() && self.skeleton_file.is_none() {
+        // Resolve each file's skeleton up front so missing ones are reported before anything runs
+        let file_skeletons = if self.conversion_mode.requires_skeleton() {
+            self.skeletons.rescan();
+            let default_skeleton = self.skeleton_file.clone();
+            let file_skeletons = self
+                .input_paths
+                .iter()
+                .map(|path| {
+                    self.skeletons
+                        .skeleton_for(path, default_skeleton.as_deref())
+                        .map(|(skeleton, _)| skeleton)
+                })
+                .collect::<Vec<_>>();
+
+            let missing = self
+                .input_paths
+                .iter()
+                .zip(&file_skeletons)
+                .filter(|(_, skeleton)| skeleton.is_none())
+                .map(|(path, _)| path.file_name().unwrap_or_default().to_string_lossy())
+                .collect::<Vec<_>>();
+            if !missing.is_empty() {
+                self.conversion_status = ConversionStatus::Error {
+                    message: format!(
+                        "No skeleton found for {}; pick a default skeleton or add a folder override",
+                        missing.join(", ")
+                    ),
+                };
+                return;
+            }
+            file_skeletons
+        } else {
+            vec![None; self.input_paths.len()]
+        };
+        // Files of a behavior project may need a different tool and keep the project's layout
+        let hct_available = self.hct_lookup.found.is_some();
+        let (file_tools, file_subfolders): (Vec<_>, Vec<_>) = self
+            .input_paths
+            .iter()
+            .map(|path| {
+                match self
+                    .projects
+                    .iter()
+                    .find_map(|scan| Some((scan, scan.kind_of(path)?)))
+                {
+                    Some((scan, kind)) => (
+                        kind.tool(self.converter_tool, hct_available),
+                        scan.relative_folder(path).unwrap_or_default(),
+                    ),
+                    None => (self.converter_tool, PathBuf::new()),
+                }
+            })
+            .unzip();
+        // Outputs written to an MO2 mod keep their place in the game's data layout
+        let file_subfolders = match (&self.mo2, &self.mo2_output_mod) {
+            (Some(mo2), Some(_)) => self
+                .input_paths
+                .iter()
+                .map(|path| {
+                    let in_mod = match bsa::split_archive_path(path) {
+                        Some(_) => None,
+                        None => mo2.folder_in_mod(path),
+                    };
+                    in_mod.unwrap_or_else(|| bsa::archive_folder_for(path).split('\\').collect())
+                })
+                .collect(),
+            _ => file_subfolders,
+        };
+        let uses_hct = file_tools.contains(&ConverterTool::Hct);
+        if uses_hct && !hct_available {
+            self.conversion_status = ConversionStatus::Error {
+                message: self.hct_lookup.explanation(),
+            };
+            return;
+        }
+        // Classes a tool can't handle fail late or vanish from the output, so those files are
+        // left out of the run and marked in the file table
+        let unavailable = self
+            .input_paths
+            .iter()
+            .zip(&file_tools)
+            .filter_map(|(path, tool)| {
+                let problems = self.kind_detector.problems(path, *tool);
+                (!problems.is_empty()).then(|| {
+                    let mut update = FileUpdate::queued(path.clone());
+                    update.state = FileState::Unavailable;
+                    update.error = Some(format!(
+                        "{} can't convert this file: {}",
+                        tool.label(),
+                        problems.join("; ")
+                    ));
+                    (path.clone(), update)
+                })
+            })
+            .collect::<HashMap<_, _>>();
+        if unavailable.len() == self.input_paths.len() {
             self.conversion_status = ConversionStatus::Error {
-                message: "Skeleton file is required for animation conversion".to_string(),
+                message: unavailable
+                    .values()
+                    .filter_map(|update| update.error.clone())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
             };
             return;
         }
+        let available = |index: &usize| !unavailable.contains_key(&self.input_paths[*index]);
+        let indices = (0..self.input_paths.len())
+            .filter(available)
+            .collect::<Vec<_>>();
+        let input_paths = indices
+            .iter()
+            .map(|&index| self.input_paths[index].clone())
+            .collect::<Vec<_>>();
+        let file_skeletons = indices
+            .iter()
+            .map(|&index| file_skeletons[index].clone())
+            .collect::<Vec<_>>();
+        let file_tools = indices
+            .iter()
+            .map(|&index| file_tools[index])
+            .collect::<Vec<_>>();
+        let file_subfolders = indices
+            .iter()
+            .map(|&index| file_subfolders[index].clone())
+            .collect::<Vec<_>>();
+        let hct_options = match self.hct_options() {
+            Ok(hct_options) => hct_options,
+            Err(e) if uses_hct => {
+                self.conversion_status = ConversionStatus::Error {
+                    message: format!("{:#}", e),
+                };
+                return;
+            }
+            // Not used by other tools
+            Err(_) => self.hct_settings.to_options(),
+        };
+
+        let hct_path = self.hct_lookup.found.clone().unwrap_or_default();
+
+        let in_place_backup_dir = if self.in_place {
+            match in_place::create_backup_dir() {
+                Ok(backup_dir) => Some(backup_dir),
+                Err(e) => {
+                    self.conversion_status = ConversionStatus::Error {
+                        message: format!("{:#}", e),
+                    };
+                    return;
+                }
+            }
+        } else {
+            None
+        };
 
         // Setup channels for progress communication
         let (progress_tx, progress_rx) = mpsc::unbounded_channel();
@@ -1043,16 +1609,20 @@ impl HkxToolsApp {
 
         self.progress_rx = Some(progress_rx);
         self.cancel_tx = Some(cancel_tx);
+        self.file_diagnostics.clear();
+        self.file_updates = input_paths
+            .iter()
+            .map(|path| (path.clone(), FileUpdate::queued(path.clone())))
+            .chain(unavailable)
+            .collect();
         self.conversion_status = ConversionStatus::Running {
             current_file: "Starting...".to_string(),
             progress: 0,
-            total: self.input_paths.len(),
+            total: input_paths.len(),
         };
 
         // Clone data needed for the async task
-        let input_paths = self.input_paths.clone();
-        let output_folder = self.output_folder.clone().unwrap();
-        let skeleton_file = self.skeleton_file.clone();
+        let output_folder = self.output_folder.clone().unwrap_or_default();
         let output_suffix = self.output_suffix.clone();
         let output_format = self.output_format;
         let custom_extension = self.custom_extension.clone();
@@ -1060,15 +1630,22 @@ impl HkxToolsApp {
         let converter_tool = self.converter_tool;
         let hkxcmd_path = self.hkxcmd_path.clone();
         let hkxconv_path = self.hkxconv_path.clone();
-        let sse_to_le_hko_path = self.sse_to_le_hko_path.clone();
         let havok_behavior_post_process_path = self.havok_behavior_post_process_path.clone();
+        let tool_options = self.settings.tool_options.clone();
+        let pack = self.pack_bsa.then(|| PackOptions {
+            archive: output_folder.join(format!("{}.bsa", self.bsa_name.trim())),
+            version: self.bsa_version,
+            compress: self.bsa_compress,
+        });
 
         // Spawn the async conversion task
         self.tokio_handle.spawn(async move {
             let result = Self::run_conversion_async(
                 input_paths,
                 output_folder,
-                skeleton_file,
+                file_skeletons,
+                file_tools,
+                file_subfolders,
                 output_suffix,
                 output_format,
                 custom_extension,
@@ -1076,8 +1653,12 @@ impl HkxToolsApp {
                 converter_tool,
                 hkxcmd_path,
                 hkxconv_path,
-                sse_to_le_hko_path,
                 havok_behavior_post_process_path,
+                hct_path,
+                hct_options,
+                tool_options,
+                in_place_backup_dir,
+                pack,
                 progress_tx,
                 cancel_rx,
             )
@@ -1091,7 +1672,9 @@ impl HkxToolsApp {
     async fn run_conversion_async(
         input_paths: Vec<PathBuf>,
         output_folder: PathBuf,
-        skeleton_file: Option<PathBuf>,
+        file_skeletons: Vec<Option<PathBuf>>,
+        file_tools: Vec<ConverterTool>,
+        file_subfolders: Vec<PathBuf>,
         output_suffix: String,
         output_format: OutputFormat,
         custom_extension: Option<String>,
@@ -1099,8 +1682,12 @@ impl HkxToolsApp {
         converter_tool: ConverterTool,
         hkxcmd_path: PathBuf,
         hkxconv_path: PathBuf,
-        sse_to_le_hko_path: PathBuf,
         havok_behavior_post_process_path: PathBuf,
+        hct_path: PathBuf,
+        hct_options: HkoOptions,
+        tool_options: ToolOptions,
+        in_place_backup_dir: Option<PathBuf>,
+        pack: Option<PackOptions>,
         progress_tx: mpsc::UnboundedSender<ConversionProgress>,
         mut cancel_rx: oneshot::Receiver<()>,
     ) -> Result<()> {
@@ -1116,6 +1703,76 @@ impl HkxToolsApp {
                 _ => "concurrent processing",
             }
         );
+
+        // Make sure every skeleton really is one before hkxcmd gets it
+        let mut skeleton_infos = HashMap::new();
+        for skeleton in file_skeletons.iter().flatten() {
+            if skeleton_infos.contains_key(skeleton) {
+                continue;
+            }
+            match SkeletonInfo::load(skeleton).await {
+                Ok(info) => {
+                    skeleton_infos.insert(skeleton.clone(), info);
+                }
+                Err(e) => {
+                    let _ = progress_tx.send(ConversionProgress {
+                        current_file: "Skeleton".to_string(),
+                        file_index: 0,
+                        total_files,
+                        status: ConversionStatus::Error {
+                            message: format!("Invalid skeleton: {:#}", e),
+                        },
+                        diagnostics: None,
+                        replaced: None,
+                        file: None,
+                    });
+                    return Ok(());
+                }
+            }
+        }
+
+        // Archives are opened once; each task extracts its own entry
+        let mut archives = HashMap::new();
+        for input_path in &input_paths {
+            let Some((archive_path, _)) = bsa::split_archive_path(input_path) else {
+                continue;
+            };
+            if archives.contains_key(&archive_path) {
+                continue;
+            }
+            match BsaArchive::open(&archive_path) {
+                Ok(archive) => {
+                    archives.insert(archive_path, Arc::new(archive));
+                }
+                Err(e) => {
+                    let _ = progress_tx.send(ConversionProgress {
+                        current_file: archive_path.to_string_lossy().to_string(),
+                        file_index: 0,
+                        total_files,
+                        status: ConversionStatus::Error {
+                            message: format!("Failed to open archive: {:#}", e),
+                        },
+                        diagnostics: None,
+                        replaced: None,
+                        file: None,
+                    });
+                    return Ok(());
+                }
+            }
+        }
+
+        // Packed outputs are staged in their archive layout, then written into the archive
+        let staging_dir = match &pack {
+            Some(_) => Some(
+                tempfile::Builder::new()
+                    .prefix("bsa_pack_")
+                    .tempdir()
+                    .context("Failed to create temporary directory for packing")?,
+            ),
+            None => None,
+        };
+        let mut packed_files: Vec<(String, PathBuf)> = Vec::new();
+
         let mut conversion_tasks = Vec::new();
 
         for (index, input_path) in input_paths.iter().enumerate() {
@@ -1128,20 +1785,72 @@ impl HkxToolsApp {
                     status: ConversionStatus::Error {
                         message: "Conversion cancelled by user".to_string(),
                     },
+                    diagnostics: None,
+                    replaced: None,
+                    file: None,
                 });
                 return Ok(());
             }
 
+            // In place, the output takes the original's name next to it
+            let (output_folder, output_suffix) = match &in_place_backup_dir {
+                Some(_) => (
+                    input_path.parent().unwrap_or(Path::new("")).to_path_buf(),
+                    "",
+                ),
+                None => (
+                    output_folder.join(&file_subfolders[index]),
+                    output_suffix.as_str(),
+                ),
+            };
             let output_path = Self::get_output_path_static(
                 input_path,
                 &output_folder,
-                &output_suffix,
+                output_suffix,
                 output_format,
                 &custom_extension,
                 conversion_mode,
             )
             .context("Failed to determine output path")?;
 
+            let (output_path, reported_output) = match (&staging_dir, &pack) {
+                (Some(staging_dir), Some(pack)) => {
+                    let archive_path = format!(
+                        "{}\\{}",
+                        bsa::archive_folder_for(input_path),
+                        output_path
+                            .file_name()
+                            .unwrap_or_default()
+                            .to_string_lossy()
+                    );
+                    if packed_files
+                        .iter()
+                        .any(|(existing, _)| *existing == archive_path)
+                    {
+                        let _ = progress_tx.send(ConversionProgress {
+                            current_file: archive_path.clone(),
+                            file_index: index,
+                            total_files,
+                            status: ConversionStatus::Error {
+                                message: format!("Two inputs would be packed as {}", archive_path),
+                            },
+                            diagnostics: None,
+                            replaced: None,
+                            file: None,
+                        });
+                        return Ok(());
+                    }
+                    let staged_path = archive_path
+                        .split('\\')
+                        .fold(staging_dir.path().to_path_buf(), |path, component| {
+                            path.join(component)
+                        });
+                    packed_files.push((archive_path.clone(), staged_path.clone()));
+                    (staged_path, pack.archive.join(archive_path))
+                }
+                _ => (output_path.clone(), output_path),
+            };
+
             if let Some(parent) = output_path.parent() {
                 fs::create_dir_all(parent).context("Failed to create output directories")?;
             }
@@ -1150,20 +1859,31 @@ impl HkxToolsApp {
 
             // Create a temporary app-like structure for the conversion tool call
             let temp_app = TempConversionContext {
-                converter_tool,
+                converter_tool: file_tools[index],
                 conversion_mode,
                 output_format,
-                skeleton_file: skeleton_file.clone(),
+                skeleton_file: file_skeletons[index].clone(),
                 hkxcmd_path: hkxcmd_path.clone(),
                 hkxconv_path: hkxconv_path.clone(),
-                sse_to_le_hko_path: sse_to_le_hko_path.clone(),
                 havok_behavior_post_process_path: havok_behavior_post_process_path.clone(),
+                hct_path: hct_path.clone(),
+                hct_options: hct_options.clone(),
+                tool_options: tool_options.clone(),
             };
 
             // Clone needed data for the async task
             let input_path_clone = input_path.clone();
             let output_path_clone = output_path.clone();
             let progress_tx_clone = progress_tx.clone();
+            let backup_dir = in_place_backup_dir.clone();
+            let archive_entry = bsa::split_archive_path(input_path)
+                .map(|(archive_path, entry_path)| (archives[&archive_path].clone(), entry_path));
+            // Animations exported to KF are compared with their skeleton first
+            let skeleton_info = file_skeletons[index]
+                .as_ref()
+                .filter(|_| conversion_mode == ConversionMode::HkxToKf)
+                .and_then(|skeleton| skeleton_infos.get(skeleton))
+                .cloned();
             let file_name = input_path
                 .file_name()
                 .unwrap_or_default()
@@ -1172,6 +1892,19 @@ impl HkxToolsApp {
 
             // Create individual conversion task
             let conversion_task = tokio::spawn(async move {
+                let started = Instant::now();
+                let file_update = |state, error: Option<&str>| {
+                    Some(FileUpdate {
+                        input: input_path_clone.clone(),
+                        state,
+                        output: Some(reported_output.clone()),
+                        duration: (state != FileState::Running).then(|| started.elapsed()),
+                        error: error.map(str::to_string),
+                        run: None,
+                        kept_temp_dir: None,
+                    })
+                };
+
                 // Send progress update when starting this file
                 let _ = progress_tx_clone.send(ConversionProgress {
                     current_file: file_name.clone(),
@@ -1182,20 +1915,111 @@ impl HkxToolsApp {
                         progress: index,
                         total: total_files,
                     },
+                    diagnostics: None,
+                    replaced: None,
+                    file: file_update(FileState::Running, None),
                 });
 
                 println!("Starting conversion of {:?}", input_path_clone);
 
+                // In place, convert to a temporary file so the original stays untouched until
+                // the output has been verified
+                let temp_dir = match &backup_dir {
+                    Some(_) => Some(
+                        tempfile::Builder::new()
+                            .prefix("hkx_in_place_")
+                            .tempdir()
+                            .context("Failed to create temporary directory")?,
+                    ),
+                    None => None,
+                };
+                let conversion_output = match &temp_dir {
+                    Some(temp_dir) => temp_dir
+                        .path()
+                        .join(output_path_clone.file_name().unwrap_or_default()),
+                    None => output_path_clone.clone(),
+                };
+
+                // Archive entries are extracted so the tools see a normal file
+                let (input_file, _extracted_dir) = match &archive_entry {
+                    Some((archive, entry_path)) => {
+                        match bsa::extract_to_temp(archive, entry_path) {
+                            Ok((input_file, extracted_dir)) => (input_file, Some(extracted_dir)),
+                            Err(e) => {
+                                let error_msg = format!("Failed to extract {}: {:#}", file_name, e);
+                                let _ = progress_tx_clone.send(ConversionProgress {
+                                    current_file: file_name.clone(),
+                                    file_index: index,
+                                    total_files,
+                                    status: ConversionStatus::Error {
+                                        message: error_msg.clone(),
+                                    },
+                                    diagnostics: None,
+                                    replaced: None,
+                                    file: file_update(FileState::Failed, Some(&error_msg)),
+                                });
+                                return Err(anyhow::anyhow!(error_msg));
+                            }
+                        }
+                    }
+                    None => (input_path_clone.clone(), None),
+                };
+
+                // A mismatch is worth a warning, but hkxcmd may still cope with it
+                let mut skeleton_warnings = Vec::new();
+                if let Some(skeleton_info) = &skeleton_info {
+                    match HkxDocument::load(&input_file).await {
+                        Ok(animation) => {
+                            skeleton_warnings = skeleton_info.check_animation(&animation)
+                        }
+                        Err(e) => skeleton_warnings
+                            .push(format!("Could not compare with the skeleton: {:#}", e)),
+                    }
+                }
+
                 // Run the actual conversion
                 let result = temp_app
-                    .run_conversion_tool(&input_path_clone, &output_path_clone)
+                    .run_conversion_tool(&input_file, &conversion_output)
                     .await;
+                let produced_format = temp_app.produced_format();
 
                 match result {
-                    Ok(_) => {
-                        if !output_path_clone.exists() {
+                    Ok(ToolOutput {
+                        mut diagnostics,
+                        hct_temp_dir,
+                        run,
+                    }) => {
+                        diagnostics.extend(skeleton_warnings.into_iter().map(|message| {
+                            HctDiagnostic {
+                                filter: None,
+                                severity: hct::Severity::Warning,
+                                message,
+                            }
+                        }));
+                        let diagnostics = (!diagnostics.is_empty()).then_some(FileDiagnostics {
+                            diagnostics,
+                            kept_temp_dir: None,
+                        });
+
+                        // Read the output back instead of trusting that it exists
+                        if let Err(e) =
+                            verify::verify_output(&conversion_output, produced_format).await
+                        {
                             let error_msg =
-                                format!("Output file was not created: {:?}", output_path_clone);
+                                format!("Output of {} failed verification: {:#}", file_name, e);
+                            // Keep HCT's log and .hko to find out why the output is broken
+                            let kept_temp_dir = hct_temp_dir.map(tempfile::TempDir::keep);
+                            let diagnostics = match &kept_temp_dir {
+                                Some(temp_dir) => {
+                                    let mut diagnostics = diagnostics.unwrap_or(FileDiagnostics {
+                                        diagnostics: Vec::new(),
+                                        kept_temp_dir: None,
+                                    });
+                                    diagnostics.kept_temp_dir = Some(temp_dir.clone());
+                                    Some(diagnostics)
+                                }
+                                None => diagnostics,
+                            };
                             let _ = progress_tx_clone.send(ConversionProgress {
                                 current_file: file_name.clone(),
                                 file_index: index,
@@ -1203,16 +2027,86 @@ impl HkxToolsApp {
                                 status: ConversionStatus::Error {
                                     message: error_msg.clone(),
                                 },
+                                diagnostics,
+                                replaced: None,
+                                file: file_update(FileState::Failed, Some(&error_msg)).map(
+                                    |update| FileUpdate {
+                                        run,
+                                        kept_temp_dir,
+                                        ..update
+                                    },
+                                ),
                             });
                             return Err(anyhow::anyhow!(error_msg));
                         }
 
+                        let replaced = match &backup_dir {
+                            Some(backup_dir) => match in_place::replace(
+                                &input_path_clone,
+                                &conversion_output,
+                                &output_path_clone,
+                                backup_dir,
+                                index,
+                            ) {
+                                Ok(replaced) => Some(replaced),
+                                Err(e) => {
+                                    let error_msg = format!(
+                                        "Failed to replace {} in place: {:#}",
+                                        file_name, e
+                                    );
+                                    let _ = progress_tx_clone.send(ConversionProgress {
+                                        current_file: file_name.clone(),
+                                        file_index: index,
+                                        total_files,
+                                        status: ConversionStatus::Error {
+                                            message: error_msg.clone(),
+                                        },
+                                        diagnostics,
+                                        replaced: None,
+                                        file: file_update(FileState::Failed, Some(&error_msg)),
+                                    });
+                                    return Err(anyhow::anyhow!(error_msg));
+                                }
+                            },
+                            None => None,
+                        };
+
+                        // Pass on the result with warnings and replaced originals
+                        let _ = progress_tx_clone.send(ConversionProgress {
+                            current_file: file_name.clone(),
+                            file_index: index,
+                            total_files,
+                            status: ConversionStatus::Running {
+                                current_file: file_name.clone(),
+                                progress: index,
+                                total: total_files,
+                            },
+                            diagnostics,
+                            replaced: replaced.clone(),
+                            file: file_update(FileState::Ok, None),
+                        });
+
                         println!("Completed conversion of {:?}", input_path_clone);
-                        let metadata = fs::metadata(&output_path_clone)?;
-                        println!("Output file size: {} bytes", metadata.len());
-                        Ok(())
+                        Ok(replaced)
                     }
                     Err(e) => {
+                        let diagnostics =
+                            e.downcast_ref::<HctFailure>()
+                                .map(|failure| FileDiagnostics {
+                                    diagnostics: failure.diagnostics.clone(),
+                                    kept_temp_dir: Some(failure.temp_dir.clone()),
+                                });
+                        // Keep the command and its output for the failure details
+                        let run = e
+                            .downcast_ref::<ToolFailure>()
+                            .map(|failure| failure.run.clone())
+                            .or_else(|| {
+                                e.downcast_ref::<HctFailure>()
+                                    .map(|failure| failure.run.clone())
+                            });
+                        let kept_temp_dir = diagnostics
+                            .as_ref()
+                            .and_then(|diagnostics| diagnostics.kept_temp_dir.clone());
                         let _ = progress_tx_clone.send(ConversionProgress {
                             current_file: file_name.clone(),
                             file_index: index,
@@ -1220,6 +2114,15 @@ impl HkxToolsApp {
                             status: ConversionStatus::Error {
                                 message: format!("Failed to convert {}: {}", file_name, e),
                             },
+                            diagnostics,
+                            replaced: None,
+                            file: file_update(FileState::Failed, Some(&e.to_string())).map(
+                                |update| FileUpdate {
+                                    run,
+                                    kept_temp_dir,
+                                    ..update
+                                },
+                            ),
                         });
                         Err(e)
                     }
@@ -1234,6 +2137,7 @@ impl HkxToolsApp {
 
         // Check results and count successes
         let mut successful_conversions = 0;
+        let mut replaced_files = Vec::new();
         for result in results {
             // Check for cancellation
             if cancel_rx.try_recv().is_ok() {
@@ -1244,13 +2148,17 @@ impl HkxToolsApp {
                     status: ConversionStatus::Error {
                         message: "Conversion cancelled by user".to_string(),
                     },
+                    diagnostics: None,
+                    replaced: None,
+                    file: None,
                 });
                 return Ok(());
             }
 
             match result {
-                Ok(Ok(())) => {
+                Ok(Ok(replaced)) => {
                     successful_conversions += 1;
+                    replaced_files.extend(replaced);
                 }
                 Ok(Err(e)) => {
                     return Err(e);
@@ -1261,23 +2169,67 @@ impl HkxToolsApp {
             }
         }
 
-        // Send completion message
-        let _ = progress_tx.send(ConversionProgress {
-            current_file: "Completed".to_string(),
-            file_index: successful_conversions,
-            total_files,
-            status: ConversionStatus::Completed {
-                message: format!(
-                    "Successfully converted {} of {} files",
-                    successful_conversions, total_files
-                ),
-            },
-        });
-
-        Ok(())
-    }
+        // Originals under another name than their output go only once every file succeeded
+        if let Err(e) = in_place::remove_originals(&replaced_files) {
+            let _ = progress_tx.send(ConversionProgress {
+                current_file: "In place".to_string(),
+                file_index: successful_conversions,
+                total_files,
+                status: ConversionStatus::Error {
+                    message: format!("{:#}", e),
+                },
+                diagnostics: None,
+                replaced: None,
+                file: None,
+            });
+            return Ok(());
+        }
 
-    // Static helper method for output path calculation
+        let mut message = format!(
+            "Successfully converted {} of {} files",
+            successful_conversions, total_files
+        );
+        if let Some(pack) = &pack {
+            if let Err(e) =
+                bsa::write_archive(&pack.archive, pack.version, pack.compress, &packed_files)
+            {
+                let _ = progress_tx.send(ConversionProgress {
+                    current_file: pack.archive.to_string_lossy().to_string(),
+                    file_index: successful_conversions,
+                    total_files,
+                    status: ConversionStatus::Error {
+                        message: format!("Failed to pack the outputs: {:#}", e),
+                    },
+                    diagnostics: None,
+                    replaced: None,
+                    file: None,
+                });
+                return Ok(());
+            }
+            message.push_str(&format!(
+                " and packed them into {}",
+                pack.archive
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+            ));
+        }
+
+        // Send completion message
+        let _ = progress_tx.send(ConversionProgress {
+            current_file: "Completed".to_string(),
+            file_index: successful_conversions,
+            total_files,
+            status: ConversionStatus::Completed { message },
+            diagnostics: None,
+            replaced: None,
+            file: None,
+        });
+
+        Ok(())
+    }
+
+    // Static helper method for output path calculation
     fn get_output_path_static(
         input_path: &Path,
         output_folder: &Path,
@@ -1318,46 +2270,128 @@ impl HkxToolsApp {
             ui.add_space(10.0);
         });
 
+        ui.horizontal(|ui| {
+            for tab in [
+                AppTab::Converter,
+                AppTab::Inspector,
+                AppTab::Annotations,
+                AppTab::AnimationReport,
+                AppTab::NemesisPatch,
+                AppTab::Settings,
+            ] {
+                if ui
+                    .selectable_label(self.active_tab == tab, tab.label())
+                    .clicked()
+                {
+                    self.active_tab = tab;
+                }
+            }
+        });
+
         ui.separator();
 
+        match self.active_tab {
+            AppTab::Converter => self.render_converter_ui(ui),
+            AppTab::Inspector => self.inspector.ui(ui),
+            AppTab::Annotations => self.annotations_tab.ui(ui),
+            AppTab::AnimationReport => self.report_tab.ui(ui),
+            AppTab::NemesisPatch => self.nemesis_tab.ui(ui),
+            AppTab::Settings => self.render_settings(ui),
+        }
+    }
+
+    fn render_settings(&mut self, ui: &mut Ui) {
+        let mut changed = false;
+
+        egui::Grid::new("settings_grid")
+            .num_columns(2)
+            .spacing([10.0, 10.0])
+            .show(ui, |ui| {
+                ui.label("HCT Path:");
+                ui.horizontal(|ui| {
+                    match &self.settings.hct_path {
+                        Some(hct_path) => ui.label(hct_path.to_string_lossy()),
+                        None => ui.label("(auto-detect)"),
+                    };
+                    if ui.button("Browse").clicked() {
+                        if let Some(file) = FileDialog::new()
+                            .add_filter("hctStandAloneFilterManager", &["exe"])
+                            .pick_file()
+                        {
+                            self.settings.hct_path = Some(file);
+                            changed = true;
+                        }
+                    }
+                    if self.settings.hct_path.is_some() && ui.button("Auto-detect").clicked() {
+                        self.settings.hct_path = None;
+                        changed = true;
+                    }
+                });
+                ui.end_row();
+
+                ui.label("");
+                let color = if self.hct_lookup.found.is_some() {
+                    Color32::from_rgb(100, 200, 100)
+                } else {
+                    Color32::from_rgb(255, 120, 120)
+                };
+                ui.label(RichText::new(self.hct_lookup.explanation()).color(color));
+                ui.end_row();
+            });
+
+        if changed {
+            self.hct_lookup = HctLookup::locate(self.settings.hct_path.as_deref());
+            if let Err(e) = self.settings.save() {
+                eprintln!("Error saving settings: {:#}", e);
+            }
+        }
+    }
+
+    fn render_converter_ui(&mut self, ui: &mut egui::Ui) {
+        // HCT may have disappeared after the path was changed in Settings
+        if self.converter_tool == ConverterTool::Hct && self.hct_lookup.found.is_none() {
+            self.select_converter_tool(ConverterTool::HkxCmd);
+        }
+        // A custom .hko decides the output format itself
+        if self.converter_tool == ConverterTool::Hct && self.hct_use_custom_hko {
+            self.sync_hct_output_format();
+        }
+
         egui::Grid::new("main_grid")
             .num_columns(2)
             .spacing([10.0, 10.0])
             .show(ui, |ui| {
                 ui.label("Converter Tool:");
                 ui.horizontal(|ui| {
-                    for tool in [
-                        ConverterTool::HkxCmd,
-                        ConverterTool::HkxC,
-                        ConverterTool::HkxConv,
-                        ConverterTool::Hct,
-                        ConverterTool::HavokBehaviorPostProcess,
-                    ] {
-                        if ui
-                            .selectable_label(self.converter_tool == tool, tool.label())
-                            .clicked()
-                        {
-                            self.converter_tool = tool;
-                            // Reset to regular mode if tool doesn't support KF conversion and we're in KF mode
-                            if !tool.supports_kf_conversion()
-                                && self.conversion_mode != ConversionMode::Regular
-                            {
-                                self.conversion_mode = ConversionMode::Regular;
-                            }
-                            // Reset input file extension if tool doesn't support current filter
-                            if !tool
-                                .available_input_extensions()
-                                .contains(&self.input_file_extension)
-                            {
-                                self.input_file_extension = InputFileExtension::Hkx;
-                            }
-                            // Reset output format if tool doesn't support current format
-                            let available_formats = self.available_output_formats_for_mode();
-                            if !available_formats.contains(&self.output_format) {
-                                if !available_formats.is_empty() {
-                                    self.output_format = available_formats[0];
-                                }
-                            }
+                    for tool in ConverterTool::ALL {
+                        // HCT is not bundled, so it can only be picked once it has been found
+                        let is_available =
+                            tool != ConverterTool::Hct || self.hct_lookup.found.is_some();
+                        let incompatible = self
+                            .input_paths
+                            .iter()
+                            .filter(|path| !self.kind_detector.problems(path, tool).is_empty())
+                            .count();
+                        let text = if incompatible > 0 {
+                            format!("{} ⛔{}", tool.label(), incompatible)
+                        } else {
+                            tool.label().to_string()
+                        };
+                        let mut response = ui
+                            .add_enabled_ui(is_available, |ui| {
+                                ui.selectable_label(self.converter_tool == tool, text)
+                            })
+                            .inner
+                            .on_disabled_hover_text(self.hct_lookup.explanation());
+                        if incompatible > 0 {
+                            response = response.on_hover_text(format!(
+                                "{} can't convert {} of the selected files",
+                                tool.label(),
+                                incompatible
+                            ));
+                        }
+                        if response.clicked() {
+                            self.select_converter_tool(tool);
                         }
                     }
                 });
@@ -1399,196 +2433,1676 @@ impl HkxToolsApp {
                 ui.horizontal(|ui| {
                     let available_filters = self.converter_tool.available_input_extensions();
 
-                    for &filter in available_filters {
-                        if ui
-                            .selectable_label(
-                                self.input_file_extension == filter,
-                                filter.label_for_tool(self.converter_tool),
-                            )
-                            .clicked()
-                        {
-                            self.input_file_extension = filter;
-                        }
-                    }
+                    for &filter in available_filters {
+                        if ui
+                            .selectable_label(
+                                self.input_file_extension == filter,
+                                filter.label_for_tool(self.converter_tool),
+                            )
+                            .clicked()
+                        {
+                            self.input_file_extension = filter;
+                        }
+                    }
+
+                    // Reset to a valid filter if current selection is not available
+                    if (self.converter_tool == ConverterTool::HkxC
+                        || self.converter_tool == ConverterTool::HkxConv)
+                        && self.input_file_extension == InputFileExtension::Kf
+                    {
+                        self.input_file_extension = InputFileExtension::Hkx;
+                    }
+                });
+                ui.end_row();
+
+                ui.label("Input Patterns:");
+                self.render_input_patterns(ui);
+                ui.end_row();
+
+                ui.label("Input Files:");
+                ui.vertical(|ui| {
+                    ui.horizontal(|ui| {
+                        if ui.button("Browse Files").clicked() {
+                            if let Some(paths) = FileDialog::new().pick_files() {
+                                let filter = self.input_filter();
+                                self.input_paths = paths
+                                    .into_iter()
+                                    .filter(|path| filter.accepts(path, path.parent()))
+                                    .collect();
+                                self.input_roots = self
+                                    .input_paths
+                                    .iter()
+                                    .filter_map(|path| {
+                                        Some((path.clone(), path.parent()?.to_path_buf()))
+                                    })
+                                    .collect();
+                                self.prune_input_state();
+                                self.update_output_folder();
+                            }
+                        }
+                        if ui.button("Select Folder").clicked() {
+                            if let Some(folder) = FileDialog::new().pick_folder() {
+                                if let Err(e) = self.add_files_from_folder(&folder, false) {
+                                    eprintln!("Error adding files from folder: {}", e);
+                                }
+                                self.update_output_folder();
+                            }
+                        }
+                        if ui.button("Select Folder (+ Subfolders)").clicked() {
+                            if let Some(folder) = FileDialog::new().pick_folder() {
+                                if let Err(e) = self.add_files_from_folder(&folder, true) {
+                                    eprintln!("Error adding files from folders: {}", e);
+                                }
+                                self.update_output_folder();
+                            }
+                        }
+                        if ui.button("Browse BSA").clicked() {
+                            if let Some(archive) = FileDialog::new()
+                                .add_filter("BSA archives", &["bsa"])
+                                .pick_file()
+                            {
+                                self.bsa_browser.open_archive(&archive);
+                            }
+                        }
+                        if ui.button("Open Project").clicked() {
+                            if let Some(project) = FileDialog::new()
+                                .add_filter("Behavior projects", &["hkx"])
+                                .pick_file()
+                            {
+                                self.load_project(project);
+                            }
+                        }
+                    });
+                    self.render_projects(ui);
+                });
+                ui.end_row();
+
+                ui.label("Mod Organizer:");
+                self.render_mo2(ui);
+                ui.end_row();
+
+                // Skeleton selection (only show for animation conversion modes)
+                if self.conversion_mode.requires_skeleton() {
+                    ui.label("Skeletons:");
+                    self.render_skeletons(ui);
+                    ui.end_row();
+                }
+
+                if self.converter_tool == ConverterTool::Hct {
+                    ui.label("HCT Options:");
+                    self.render_hct_options(ui);
+                    ui.end_row();
+                }
+
+                if matches!(
+                    self.converter_tool,
+                    ConverterTool::HkxCmd
+                        | ConverterTool::HkxConv
+                        | ConverterTool::HavokBehaviorPostProcess
+                ) {
+                    ui.label("Tool Options:");
+                    self.render_tool_options(ui);
+                    ui.end_row();
+                }
+
+                ui.label("Output Folder:");
+                self.render_output_folder(ui);
+                ui.end_row();
+
+                ui.label("Pack into BSA:");
+                self.render_pack_bsa(ui);
+                ui.end_row();
+
+                ui.label("Output Suffix:");
+                ui.add_enabled(
+                    !self.in_place,
+                    egui::TextEdit::singleline(&mut self.output_suffix),
+                );
+                ui.end_row();
+
+                ui.label("Custom Extension:");
+                ui.horizontal(|ui| {
+                    let mut extension_text =
+                        self.custom_extension.as_ref().cloned().unwrap_or_default();
+                    if ui.text_edit_singleline(&mut extension_text).changed() {
+                        self.custom_extension = if extension_text.is_empty() {
+                            None
+                        } else {
+                            Some(extension_text)
+                        };
+                    }
+                    // ui.label("(optional - leave empty to use format default)");
+                });
+                ui.end_row();
+
+                ui.label("Output Format:");
+                self.render_output_format(ui);
+                ui.end_row();
+            });
+
+        ui.add_space(10.0);
+
+        // Selected Files section outside the grid for more space
+        ui.horizontal(|ui| {
+            ui.label("Selected Files:");
+            ui.label(format!("{} files selected", self.input_paths.len()));
+            if ui.button("Clear All").clicked() {
+                self.input_paths.clear();
+                self.input_roots.clear();
+                self.projects.clear();
+                self.file_updates.clear();
+                self.selected_files.clear();
+                self.selection_anchor = None;
+            }
+            ui.label("Kind:");
+            egui::ComboBox::from_id_salt("kind_filter")
+                .selected_text(self.kind_filter.map_or("All kinds", |kind| kind.label()))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.kind_filter, None, "All kinds");
+                    for kind in FileKind::ALL {
+                        ui.selectable_value(&mut self.kind_filter, Some(kind), kind.label());
+                    }
+                });
+            if let Some(filter) = self.kind_filter {
+                if ui.button("Remove Other Kinds").clicked() {
+                    let detector = &mut self.kind_detector;
+                    self.input_paths
+                        .retain(|path| detector.known_kind(path).is_none_or(|kind| kind == filter));
+                    self.prune_input_state();
+                }
+            }
+        });
+
+        // Behaviors and skeletons queued for a KF export would fail or produce garbage
+        if self.conversion_mode.requires_skeleton() {
+            let mut not_animations = HashMap::new();
+            for path in &self.input_paths {
+                match self.kind_detector.known_kind(path) {
+                    Some(FileKind::Animation) | None => {}
+                    Some(kind) => *not_animations.entry(kind).or_insert(0) += 1,
+                }
+            }
+            for kind in FileKind::ALL {
+                if let Some(count) = not_animations.get(&kind) {
+                    ui.label(
+                        RichText::new(format!(
+                            "⚠ {} {} files are queued as animations",
+                            count,
+                            kind.label()
+                        ))
+                        .color(Color32::from_rgb(255, 180, 0)),
+                    );
+                }
+            }
+        }
+
+        // Show drag and drop hint
+        ui.horizontal(|ui| {
+            ui.label(
+                RichText::new(
+                    "💡 Tip: You can drag and drop files or folders directly onto this window",
+                )
+                .color(Color32::from_rgb(100, 100, 100))
+                .size(12.0),
+            );
+        });
+
+        // Show HCT processing note
+        // if self.converter_tool == ConverterTool::Hct {
+        //     ui.horizontal(|ui| {
+        //         ui.label(RichText::new("ℹ️ HCT files use isolated temp directories for safe concurrent processing").color(Color32::from_rgb(100, 100, 100)).size(12.0));
+        //     });
+        // }
+
+        // File table with a fixed height so the controls below stay in view
+        let mut files_to_remove = ui
+            .allocate_ui_with_layout(
+                egui::Vec2::new(ui.available_width(), 230.0),
+                egui::Layout::top_down(egui::Align::LEFT),
+                |ui| self.render_file_table(ui),
+            )
+            .inner;
+
+        // Remove files after the table; rows may be sorted, so go by index
+        files_to_remove.sort_unstable();
+        files_to_remove.dedup();
+        for index in files_to_remove.iter().rev() {
+            self.input_paths.remove(*index);
+        }
+        if !files_to_remove.is_empty() {
+            self.prune_input_state();
+        }
+
+        ui.add_space(10.0);
+
+        self.handle_conversion(ui);
+    }
+
+    /// Include/exclude globs applied to every added input, with how many files they keep
+    fn render_input_patterns(&mut self, ui: &mut Ui) {
+        // The patterns apply while typing but are saved once a field is left
+        let mut changed = false;
+        let mut removed_unmatched = false;
+        ui.vertical(|ui| {
+            ui.horizontal(|ui| {
+                ui.label("Include:");
+                changed |= ui
+                    .add(
+                        egui::TextEdit::singleline(&mut self.settings.include_globs)
+                            .hint_text("e.g. actors/**")
+                            .desired_width(180.0),
+                    )
+                    .lost_focus();
+                ui.label("Exclude:");
+                changed |= ui
+                    .add(
+                        egui::TextEdit::singleline(&mut self.settings.exclude_globs)
+                            .hint_text("e.g. _1stperson/**; *_backup.hkx")
+                            .desired_width(220.0),
+                    )
+                    .lost_focus();
+                changed |= ui
+                    .checkbox(&mut self.settings.recursive_drop, "Recursive folder drop")
+                    .on_hover_text("Dropped folders add the files of their subfolders too")
+                    .changed();
+            })
+            .response
+            .on_hover_text(
+                "Patterns are separated by ';'. * and ? match within a name, ** any number of \
+                 folders. Patterns without / match the file name.",
+            );
+
+            let filter = self.input_filter();
+            if filter.is_empty() {
+                return;
+            }
+            if let Some((folder, recursive)) = self.last_input_folder.clone() {
+                let tag = format!("{:?} {:?}", self.converter_tool, self.input_file_extension);
+                let (converter_tool, input_file_extension) =
+                    (self.converter_tool, self.input_file_extension);
+                self.input_preview.update(
+                    &self.tokio_handle,
+                    &folder,
+                    recursive,
+                    &filter,
+                    tag,
+                    move |path| Self::file_matches(converter_tool, input_file_extension, path),
+                );
+                let preview = &self.input_preview;
+                let text = if preview.is_counting() {
+                    ui.ctx().request_repaint();
+                    format!(
+                        "Counting files in {}...",
+                        folder.file_name().unwrap_or_default().to_string_lossy()
+                    )
+                } else {
+                    format!(
+                        "{} of {} files in {} match",
+                        preview.matched,
+                        preview.total,
+                        folder.file_name().unwrap_or_default().to_string_lossy()
+                    )
+                };
+                ui.label(RichText::new(text).color(Color32::from_rgb(100, 100, 100)));
+            }
+            // Each file is matched below the folder it was added from, as when it was queued
+            let roots = &self.input_roots;
+            let accepts =
+                |path: &PathBuf| filter.accepts(path, roots.get(path).map(PathBuf::as_path));
+            let unmatched = self
+                .input_paths
+                .iter()
+                .filter(|path| !accepts(path))
+                .count();
+            if unmatched > 0 {
+                ui.horizontal(|ui| {
+                    ui.label(
+                        RichText::new(format!("{} queued files don't match", unmatched))
+                            .color(Color32::from_rgb(255, 180, 0)),
+                    );
+                    if ui.button("Remove Them").clicked() {
+                        self.input_paths.retain(|path| accepts(path));
+                        removed_unmatched = true;
+                    }
+                });
+            }
+        });
+        if removed_unmatched {
+            self.prune_input_state();
+        }
+
+        if changed {
+            if let Err(e) = self.settings.save() {
+                eprintln!("Failed to save settings: {:#}", e);
+            }
+        }
+    }
+
+    /// The input list as a sortable, searchable table with multi-select; returns the indices
+    /// of files to remove
+    fn render_file_table(&mut self, ui: &mut Ui) -> Vec<usize> {
+        let common_folder = self.find_common_parent_dir().map(Path::to_path_buf);
+        let mut rows = file_table::rows(
+            &self.input_paths,
+            common_folder.as_deref(),
+            &mut self.kind_detector,
+            &self.file_updates,
+            self.file_sort,
+        );
+        if let Some(filter) = self.kind_filter {
+            // Files still being read stay listed until their kind is known
+            rows.retain(|row| {
+                row.kind == Some(filter) || (row.kind.is_none() && row.error.is_none())
+            });
+        }
+        let search = self.file_search.to_lowercase();
+        rows.retain(|row| row.matches_search(&search));
+
+        let mut files_to_remove = Vec::new();
+        let shown = rows.iter().map(|row| row.index).collect::<Vec<_>>();
+        let selected = (0..self.input_paths.len())
+            .filter(|&index| self.selected_files.contains(&self.input_paths[index]))
+            .collect::<Vec<_>>();
+        ui.horizontal(|ui| {
+            ui.label("Search:");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.file_search)
+                    .hint_text("name or folder")
+                    .desired_width(200.0),
+            );
+            if !self.file_search.is_empty() && ui.small_button("✖").clicked() {
+                self.file_search.clear();
+            }
+            ui.checkbox(&mut self.group_by_folder, "Group by folder");
+            ui.separator();
+            if ui.button("Select All Shown").clicked() {
+                let paths = shown.iter().map(|&index| self.input_paths[index].clone());
+                self.selected_files.extend(paths);
+            }
+            if !selected.is_empty() {
+                if ui.button("Clear Selection").clicked() {
+                    self.selected_files.clear();
+                }
+                if ui
+                    .button(format!("Remove Selected ({})", selected.len()))
+                    .clicked()
+                {
+                    files_to_remove.extend(&selected);
+                }
+            }
+            let filtered = !search.is_empty() || self.kind_filter.is_some();
+            if filtered
+                && !shown.is_empty()
+                && ui
+                    .button(format!("Remove Shown ({})", shown.len()))
+                    .on_hover_text("Remove every file matching the search and kind")
+                    .clicked()
+            {
+                files_to_remove.extend(&shown);
+            }
+        });
+
+        let lines = if self.group_by_folder {
+            file_table::group_by_folder(rows, &self.collapsed_folders)
+        } else {
+            rows.into_iter().map(TableLine::File).collect()
+        };
+        // Inputs in the order they are listed, for shift-click ranges
+        let order = lines
+            .iter()
+            .filter_map(|line| match line {
+                TableLine::File(file) => Some(file.index),
+                TableLine::Folder { .. } => None,
+            })
+            .collect::<Vec<_>>();
+
+        let show_skeletons = self.conversion_mode.requires_skeleton();
+        let mut clicked_column = None;
+        let mut clicked_failure = None;
+        let mut clicked_file = None;
+        let mut toggled_folder = None;
+        let row_height = ui.text_style_height(&egui::TextStyle::Body) + 4.0;
+
+        let mut table = TableBuilder::new(ui)
+            .id_salt("input_files")
+            .striped(true)
+            .resizable(true)
+            .sense(egui::Sense::click())
+            .auto_shrink([false, true])
+            .column(Column::exact(20.0))
+            .column(Column::initial(200.0).at_least(100.0).clip(true))
+            .column(Column::initial(150.0).at_least(60.0).clip(true))
+            .columns(Column::auto().at_least(50.0), 5)
+            .column(Column::initial(250.0).at_least(80.0).clip(true));
+        if show_skeletons {
+            table = table.column(Column::remainder().at_least(100.0).clip(true));
+        }
+        table
+            .header(row_height + 2.0, |mut header| {
+                header.col(|_| {});
+                for column in FileColumn::ALL {
+                    header.col(|ui| {
+                        let arrow = match self.file_sort {
+                            Some((sorted, true)) if sorted == column => " ⏶",
+                            Some((sorted, false)) if sorted == column => " ⏷",
+                            _ => "",
+                        };
+                        if ui
+                            .button(RichText::new(format!("{}{}", column.label(), arrow)).strong())
+                            .clicked()
+                        {
+                            clicked_column = Some(column);
+                        }
+                    });
+                }
+                if show_skeletons {
+                    header.col(|ui| {
+                        ui.strong("Skeleton");
+                    });
+                }
+            })
+            .body(|body| {
+                body.rows(row_height, lines.len(), |mut row| {
+                    let file = match &lines[row.index()] {
+                        TableLine::Folder {
+                            folder,
+                            files,
+                            collapsed,
+                        } => {
+                            row.col(|ui| {
+                                ui.label(if *collapsed { "⏵" } else { "⏷" });
+                            });
+                            row.col(|ui| {
+                                let name = if folder.is_empty() { "." } else { folder };
+                                ui.strong(format!("{} ({} files)", name, files));
+                            });
+                            if row.response().clicked() {
+                                toggled_folder = Some(folder.clone());
+                            }
+                            return;
+                        }
+                        TableLine::File(file) => file,
+                    };
+                    let path = self.input_paths[file.index].clone();
+                    row.set_selected(self.selected_files.contains(&path));
+                    row.col(|ui| {
+                        if ui.small_button("❌").clicked() {
+                            files_to_remove.push(file.index);
+                        }
+                    });
+                    row.col(|ui| {
+                        ui.label(&file.name).on_hover_text(path.to_string_lossy());
+                    });
+                    row.col(|ui| {
+                        ui.label(&file.folder);
+                    });
+                    row.col(|ui| {
+                        ui.label(file.size.map(file_table::format_size).unwrap_or_default());
+                    });
+                    row.col(|ui| {
+                        ui.label(file.format.unwrap_or_default());
+                    });
+                    row.col(|ui| {
+                        self.render_kind_cell(ui, &path, file, show_skeletons);
+                    });
+                    row.col(|ui| match file.state {
+                        Some(state @ (FileState::Failed | FileState::Unavailable)) => {
+                            let hover = match state {
+                                FileState::Failed => "Show the command and its output",
+                                _ => "Show why the converter can't handle this file",
+                            };
+                            if ui
+                                .link(RichText::new(state.label()).color(state.color()))
+                                .on_hover_text(hover)
+                                .clicked()
+                            {
+                                clicked_failure = Some(path.clone());
+                            }
+                        }
+                        Some(state) => {
+                            ui.label(RichText::new(state.label()).color(state.color()));
+                        }
+                        None => {}
+                    });
+                    row.col(|ui| {
+                        ui.label(
+                            file.duration
+                                .map(file_table::format_duration)
+                                .unwrap_or_default(),
+                        );
+                    });
+                    row.col(|ui| {
+                        if let Some(output) = &file.output {
+                            ui.label(output.to_string_lossy());
+                        }
+                    });
+                    if show_skeletons {
+                        row.col(|ui| {
+                            let skeleton = self
+                                .skeletons
+                                .skeleton_for(&path, self.skeleton_file.as_deref());
+                            let text = match skeleton {
+                                Some((skeleton, source)) => RichText::new(format!(
+                                    "{} ({})",
+                                    skeletons::short_path(&skeleton),
+                                    source.label()
+                                ))
+                                .color(Color32::from_rgb(100, 100, 100)),
+                                None => RichText::new("no skeleton")
+                                    .color(Color32::from_rgb(255, 120, 120)),
+                            };
+                            ui.label(text);
+                        });
+                    }
+                    if row.response().clicked() {
+                        clicked_file = Some(file.index);
+                    }
+                });
+            });
+
+        if let Some(folder) = toggled_folder {
+            if !self.collapsed_folders.remove(&folder) {
+                self.collapsed_folders.insert(folder);
+            }
+        }
+        if let Some(index) = clicked_file {
+            let modifiers = ui.input(|i| i.modifiers);
+            self.select_file(index, &order, modifiers);
+        }
+        if clicked_failure.is_some() {
+            self.failure_detail = clicked_failure;
+        }
+        if let Some(column) = clicked_column {
+            self.file_sort = match self.file_sort {
+                Some((sorted, ascending)) if sorted == column => Some((column, !ascending)),
+                _ => Some((column, true)),
+            };
+        }
+        files_to_remove
+    }
+
+    /// Click selection: a plain click selects only `index`, ctrl toggles it and shift selects
+    /// every listed file from the last clicked one
+    fn select_file(&mut self, index: usize, order: &[usize], modifiers: egui::Modifiers) {
+        let path = self.input_paths[index].clone();
+        let anchor = self.selection_anchor.as_ref().and_then(|anchor| {
+            order
+                .iter()
+                .position(|&listed| self.input_paths[listed] == *anchor)
+        });
+        match anchor {
+            Some(anchor) if modifiers.shift => {
+                let clicked = order.iter().position(|&listed| listed == index);
+                let clicked = clicked.unwrap_or(anchor);
+                if !modifiers.command {
+                    self.selected_files.clear();
+                }
+                let range = &order[anchor.min(clicked)..=anchor.max(clicked)];
+                let paths = range.iter().map(|&listed| self.input_paths[listed].clone());
+                self.selected_files.extend(paths);
+            }
+            _ if modifiers.command => {
+                if !self.selected_files.remove(&path) {
+                    self.selected_files.insert(path.clone());
+                }
+                self.selection_anchor = Some(path);
+            }
+            _ => {
+                self.selected_files = HashSet::from([path.clone()]);
+                self.selection_anchor = Some(path);
+            }
+        }
+    }
+
+    /// Detected kind with its classes on hover, the tools that can't convert the file and
+    /// the tool a behavior project picks for it
+    fn render_kind_cell(&mut self, ui: &mut Ui, path: &Path, file: &FileRow, show_skeletons: bool) {
+        if let Some(error) = &file.error {
+            ui.label(RichText::new("unreadable").color(Color32::from_rgb(255, 120, 120)))
+                .on_hover_text(error);
+            return;
+        }
+        let Some(Ok(info)) = self.kind_detector.info(path) else {
+            ui.spinner();
+            return;
+        };
+
+        let misplaced = show_skeletons && info.kind != FileKind::Animation;
+        let color = if misplaced {
+            Color32::from_rgb(255, 180, 0)
+        } else {
+            Color32::from_rgb(100, 100, 100)
+        };
+        let response = ui.label(RichText::new(info.kind.label()).color(color));
+        if let Some(classes) = &info.classes {
+            response.on_hover_ui(|ui| {
+                Self::render_class_report(ui, classes);
+            });
+            Self::render_unavailable_tools(ui, classes, self.converter_tool);
+        }
+        if let Some(kind) = self.projects.iter().find_map(|scan| scan.kind_of(path)) {
+            let tool = kind.tool(self.converter_tool, self.hct_lookup.found.is_some());
+            if tool != self.converter_tool {
+                ui.label(
+                    RichText::new(format!("[{}]", tool.label()))
+                        .color(Color32::from_rgb(100, 100, 100)),
+                );
+            }
+        }
+    }
+
+    /// Classes and versions of an input, for the file list's hover text
+    fn render_class_report(ui: &mut Ui, report: &ClassReport) {
+        if let Some(version) = &report.contents_version {
+            ui.label(format!("Contents version: {}", version));
+        }
+        egui::Grid::new("class_report")
+            .striped(true)
+            .show(ui, |ui| {
+                for class in &report.classes {
+                    ui.label(&class.name);
+                    ui.label(
+                        class
+                            .signature
+                            .map(|signature| format!("0x{:08x}", signature))
+                            .unwrap_or_default(),
+                    );
+                    ui.label(format!("×{}", class.count));
+                    ui.end_row();
+                }
+            });
+    }
+
+    /// Tools that can't convert a file, in red when the selected tool is one of them
+    fn render_unavailable_tools(ui: &mut Ui, report: &ClassReport, selected_tool: ConverterTool) {
+        let unavailable = ConverterTool::ALL
+            .into_iter()
+            .map(|tool| (tool, report.problems(tool)))
+            .filter(|(_, problems)| !problems.is_empty())
+            .collect::<Vec<_>>();
+        if unavailable.is_empty() {
+            return;
+        }
+
+        let names = unavailable
+            .iter()
+            .map(|(tool, _)| tool.label())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let color = if unavailable.iter().any(|(tool, _)| *tool == selected_tool) {
+            Color32::from_rgb(255, 120, 120)
+        } else {
+            Color32::from_rgb(100, 100, 100)
+        };
+        let reasons = unavailable
+            .iter()
+            .map(|(tool, problems)| format!("{}:\n  {}", tool.label(), problems.join("\n  ")))
+            .collect::<Vec<_>>()
+            .join("\n");
+        ui.label(RichText::new(format!("⛔ {}", names)).color(color))
+            .on_hover_text(reasons);
+    }
+
+    fn render_projects(&mut self, ui: &mut Ui) {
+        match self.project_load.as_mut().map(|rx| rx.try_recv()) {
+            None => {}
+            Some(Err(oneshot::error::TryRecvError::Empty)) => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("Reading project...");
+                });
+                ui.ctx().request_repaint();
+            }
+            Some(Ok(Ok(scan))) => {
+                self.project_load = None;
+                self.queue_project(scan);
+            }
+            Some(Ok(Err(e))) => {
+                self.project_load = None;
+                self.project_error = Some(format!("{:#}", e));
+            }
+            Some(Err(oneshot::error::TryRecvError::Closed)) => {
+                self.project_load = None;
+                self.project_error = Some("Reading the project was interrupted".to_string());
+            }
+        }
+        if let Some(error) = &self.project_error {
+            ui.label(RichText::new(error).color(Color32::from_rgb(255, 120, 120)));
+        }
+
+        let mut project_to_remove = None;
+        for (index, scan) in self.projects.iter().enumerate() {
+            ui.horizontal(|ui| {
+                if ui
+                    .small_button("❌")
+                    .on_hover_text("Stop treating these files as a project")
+                    .clicked()
+                {
+                    project_to_remove = Some(index);
+                }
+                ui.label(format!(
+                    "{}: {} files",
+                    scan.project
+                        .file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy(),
+                    scan.files.len()
+                ));
+                if let Some(skeleton) = &scan.skeleton {
+                    ui.label(
+                        RichText::new(format!("rig {}", skeletons::short_path(skeleton)))
+                            .color(Color32::from_rgb(100, 100, 100)),
+                    );
+                }
+            });
+            if !scan.missing.is_empty() {
+                egui::CollapsingHeader::new(
+                    RichText::new(format!("{} missing files", scan.missing.len()))
+                        .color(Color32::from_rgb(255, 120, 120)),
+                )
+                .id_salt(("project_missing", index))
+                .show(ui, |ui| {
+                    for missing in &scan.missing {
+                        ui.label(format!(
+                            "{} (referenced by {})",
+                            missing.path.to_string_lossy(),
+                            missing
+                                .referenced_by
+                                .file_name()
+                                .unwrap_or_default()
+                                .to_string_lossy()
+                        ));
+                    }
+                });
+            }
+        }
+        if let Some(index) = project_to_remove {
+            self.projects.remove(index);
+        }
+    }
+
+    fn render_mo2(&mut self, ui: &mut Ui) {
+        let mut folder_to_add = None;
+        ui.vertical(|ui| {
+            ui.horizontal(|ui| {
+                if ui.button("Open ModOrganizer.ini").clicked() {
+                    if let Some(ini) = FileDialog::new()
+                        .add_filter("ModOrganizer.ini", &["ini"])
+                        .pick_file()
+                    {
+                        match Mo2Instance::open(&ini) {
+                            Ok(instance) => {
+                                self.mo2 = Some(instance);
+                                self.mo2_error = None;
+                                self.mo2_input_mod = None;
+                                self.mo2_output_mod = None;
+                                self.settings.mo2_ini = Some(ini);
+                                if let Err(e) = self.settings.save() {
+                                    eprintln!("Failed to save settings: {:#}", e);
+                                }
+                            }
+                            Err(e) => self.mo2_error = Some(format!("{:#}", e)),
+                        }
+                    }
+                }
+                if let Some(mo2) = &mut self.mo2 {
+                    ui.label(mo2.ini_path.to_string_lossy());
+                    let mut profile = mo2.profile.clone();
+                    egui::ComboBox::from_id_salt("mo2_profile")
+                        .selected_text(&profile)
+                        .show_ui(ui, |ui| {
+                            for name in &mo2.profiles {
+                                ui.selectable_value(&mut profile, name.clone(), name);
+                            }
+                        });
+                    if profile != mo2.profile {
+                        if let Err(e) = mo2.select_profile(&profile) {
+                            self.mo2_error = Some(format!("{:#}", e));
+                        }
+                    }
+                }
+            });
+            if let Some(error) = &self.mo2_error {
+                ui.label(RichText::new(error).color(Color32::from_rgb(255, 120, 120)));
+            }
+            let Some(mo2) = &self.mo2 else {
+                return;
+            };
+
+            ui.horizontal(|ui| {
+                ui.label("Input mod:");
+                egui::ComboBox::from_id_salt("mo2_input_mod")
+                    .selected_text(self.mo2_input_mod.as_deref().unwrap_or("Pick a mod"))
+                    .show_ui(ui, |ui| {
+                        for mo2_mod in &mo2.mods {
+                            let text = if mo2_mod.enabled {
+                                RichText::new(&mo2_mod.name)
+                            } else {
+                                RichText::new(format!("{} (disabled)", mo2_mod.name)).weak()
+                            };
+                            ui.selectable_value(
+                                &mut self.mo2_input_mod,
+                                Some(mo2_mod.name.clone()),
+                                text,
+                            );
+                        }
+                    });
+                if ui
+                    .add_enabled(
+                        self.mo2_input_mod.is_some(),
+                        egui::Button::new("Add Mod Files"),
+                    )
+                    .clicked()
+                {
+                    folder_to_add = self
+                        .mo2_input_mod
+                        .as_deref()
+                        .map(|name| mo2.mod_folder(name));
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Output mod:");
+                egui::ComboBox::from_id_salt("mo2_output_mod")
+                    .selected_text(
+                        self.mo2_output_mod
+                            .as_deref()
+                            .unwrap_or("None (use the output folder)"),
+                    )
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(
+                            &mut self.mo2_output_mod,
+                            None,
+                            "None (use the output folder)",
+                        );
+                        for mo2_mod in &mo2.mods {
+                            ui.selectable_value(
+                                &mut self.mo2_output_mod,
+                                Some(mo2_mod.name.clone()),
+                                &mo2_mod.name,
+                            );
+                        }
+                    });
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.mo2_new_mod)
+                        .hint_text("New mod name")
+                        .desired_width(150.0),
+                );
+                let new_mod = self.mo2_new_mod.trim().to_string();
+                if ui
+                    .add_enabled(!new_mod.is_empty(), egui::Button::new("Create"))
+                    .on_hover_text("MO2 lists the new mod after a refresh")
+                    .clicked()
+                {
+                    match fs::create_dir_all(mo2.mod_folder(&new_mod)) {
+                        Ok(()) => {
+                            self.mo2_output_mod = Some(new_mod);
+                            self.mo2_new_mod.clear();
+                        }
+                        Err(e) => {
+                            self.mo2_error = Some(format!("Failed to create mod folder: {}", e))
+                        }
+                    }
+                }
+            });
+
+            if let Some(name) = &self.mo2_output_mod {
+                self.output_folder = Some(mo2.mod_folder(name));
+                ui.label(
+                    RichText::new(format!(
+                        "Outputs keep their meshes\\... folders inside {}",
+                        name
+                    ))
+                    .color(Color32::from_rgb(100, 100, 100))
+                    .size(12.0),
+                );
+            }
+        });
+
+        if let Some(folder) = folder_to_add {
+            if let Err(e) = self.add_files_from_folder(&folder, true) {
+                eprintln!("Error adding files from mod: {}", e);
+            }
+            self.update_output_folder();
+        }
+    }
+
+    fn render_skeletons(&mut self, ui: &mut Ui) {
+        ui.vertical(|ui| {
+            ui.horizontal(|ui| {
+                ui.label("Default:");
+                if let Some(ref skeleton_file) = self.skeleton_file {
+                    ui.label(
+                        skeleton_file
+                            .file_name()
+                            .unwrap_or_default()
+                            .to_string_lossy(),
+                    );
+                }
+                if ui.button("Browse").clicked() {
+                    if let Some(file) = FileDialog::new()
+                        .add_filter("HKX files", &["hkx"])
+                        .pick_file()
+                    {
+                        self.skeleton_file = Some(file);
+                    }
+                }
+                if self.skeleton_file.is_some() && ui.button("Clear").clicked() {
+                    self.skeleton_file = None;
+                }
+            });
+            if let Some(skeleton_file) = &self.skeleton_file {
+                Self::render_skeleton_status(ui, &mut self.skeleton_validator, skeleton_file);
+            }
+            ui.label(
+                RichText::new(
+                    "Files under meshes/actors/<creature>/ use the nearest character assets/skeleton.hkx",
+                )
+                .color(Color32::from_rgb(100, 100, 100))
+                .size(12.0),
+            );
+
+            let mut override_to_remove = None;
+            for (index, (folder, skeleton)) in self.skeletons.overrides.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    if ui.small_button("❌").clicked() {
+                        override_to_remove = Some(index);
+                    }
+                    ui.label(format!(
+                        "{} → {}",
+                        folder.to_string_lossy(),
+                        skeletons::short_path(skeleton)
+                    ));
+                    Self::render_skeleton_status(ui, &mut self.skeleton_validator, skeleton);
+                });
+            }
+            if let Some(index) = override_to_remove {
+                self.skeletons.overrides.remove(index);
+            }
+
+            if ui.button("Add Folder Override").clicked() {
+                if let Some(folder) = FileDialog::new()
+                    .set_title("Folder to use a different skeleton for")
+                    .pick_folder()
+                {
+                    if let Some(skeleton) = FileDialog::new()
+                        .set_title("Skeleton for this folder")
+                        .add_filter("HKX files", &["hkx"])
+                        .pick_file()
+                    {
+                        self.skeletons.set_override(folder, skeleton);
+                    }
+                }
+            }
+        });
+    }
+
+    /// Show what a picked skeleton contains, or why it isn't usable
+    fn render_skeleton_status(ui: &mut Ui, validator: &mut SkeletonValidator, skeleton: &Path) {
+        match validator.status(skeleton) {
+            None => {
+                ui.spinner();
+                ui.ctx().request_repaint();
+            }
+            Some(Ok(info)) => {
+                ui.label(
+                    RichText::new(format!("✔ {}", info.describe()))
+                        .color(Color32::from_rgb(100, 200, 100)),
+                );
+            }
+            Some(Err(e)) => {
+                ui.label(RichText::new(format!("✖ {}", e)).color(Color32::from_rgb(255, 120, 120)));
+            }
+        }
+    }
+
+    fn render_output_folder(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.add_enabled_ui(!self.in_place, |ui| {
+                if let Some(ref output_folder) = self.output_folder {
+                    ui.label(output_folder.to_string_lossy());
+                }
+                if ui.button("Browse").clicked() {
+                    if let Some(folder) = FileDialog::new().pick_folder() {
+                        self.output_folder = Some(folder);
+                        self.mo2_output_mod = None;
+                    }
+                }
+            });
+            ui.checkbox(&mut self.in_place, "Convert in place")
+                .on_hover_text(
+                    "Replace each input file with its converted version. \
+                     Originals are backed up first and can be restored with Undo.",
+                );
+        });
+    }
+
+    fn render_pack_bsa(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.add_enabled(
+                !self.in_place,
+                egui::Checkbox::without_text(&mut self.pack_bsa),
+            )
+            .on_hover_text(
+                "Write the converted files into a new archive in the output folder, \
+                     keeping their meshes\\... layout",
+            );
+            ui.add_enabled_ui(self.pack_bsa && !self.in_place, |ui| {
+                ui.add(egui::TextEdit::singleline(&mut self.bsa_name).desired_width(150.0));
+                ui.label(".bsa");
+                for version in [BsaVersion::Le, BsaVersion::Se] {
+                    ui.radio_value(&mut self.bsa_version, version, version.label());
+                }
+                ui.checkbox(&mut self.bsa_compress, "Compress");
+            });
+        });
+    }
+
+    /// Command, exit code and output of the failed file picked in the file table
+    fn render_failure_detail(&mut self, ctx: &EguiContext) {
+        let Some(input) = self.failure_detail.clone() else {
+            return;
+        };
+        let Some(update) = self.file_updates.get(&input) else {
+            self.failure_detail = None;
+            return;
+        };
+
+        let mut open = true;
+        egui::Window::new(format!(
+            "{}: {}",
+            match update.state {
+                FileState::Unavailable => "Not converted",
+                _ => "Failed",
+            },
+            input.file_name().unwrap_or_default().to_string_lossy()
+        ))
+        .id(egui::Id::new("failure_detail"))
+        .open(&mut open)
+        .default_size([700.0, 500.0])
+        .show(ctx, |ui| {
+            if let Some(error) = &update.error {
+                ui.label(RichText::new(error).color(Color32::from_rgb(255, 120, 120)));
+            }
+            ui.horizontal(|ui| {
+                if ui.button("Open in Explorer").clicked() {
+                    // Archive entries only exist inside their archive
+                    let target = bsa::split_archive_path(&input)
+                        .map(|(archive, _)| archive)
+                        .unwrap_or_else(|| input.clone());
+                    if let Err(e) = tool_run::open_in_explorer(&target) {
+                        eprintln!("{:#}", e);
+                    }
+                }
+                if let Some(run) = &update.run {
+                    if ui.button("Copy Command").clicked() {
+                        ui.ctx().copy_text(run.command_line.clone());
+                    }
+                }
+            });
+
+            if let Some(temp_dir) = &update.kept_temp_dir {
+                ui.horizontal(|ui| {
+                    ui.label("HCT files kept in:");
+                    ui.label(RichText::new(temp_dir.to_string_lossy()).monospace());
+                    if ui.small_button("Open").clicked() {
+                        if let Err(e) = tool_run::open_in_explorer(temp_dir) {
+                            eprintln!("{:#}", e);
+                        }
+                    }
+                });
+            }
+
+            let Some(run) = &update.run else {
+                ui.label("The file failed before or after the converter ran, so there is no tool output.");
+                return;
+            };
+            egui::Grid::new("failure_run")
+                .num_columns(2)
+                .spacing([10.0, 4.0])
+                .show(ui, |ui| {
+                    ui.label("Command:");
+                    ui.label(RichText::new(&run.command_line).monospace());
+                    ui.end_row();
+
+                    ui.label("Working folder:");
+                    ui.label(RichText::new(run.working_dir.to_string_lossy()).monospace());
+                    ui.end_row();
+
+                    ui.label("Exit code:");
+                    ui.label(match run.exit_code {
+                        Some(code) => code.to_string(),
+                        None if run.in_process => "none (converted inside this program)".to_string(),
+                        None => "none (terminated)".to_string(),
+                    });
+                    ui.end_row();
+                });
+            ui.separator();
+            Self::render_output_pane(ui, "stdout", &run.stdout);
+            Self::render_output_pane(ui, "stderr", &run.stderr);
+        });
+
+        if !open {
+            self.failure_detail = None;
+        }
+    }
+
+    /// Read-only, selectable tool output
+    fn render_output_pane(ui: &mut Ui, name: &str, output: &str) {
+        ui.label(RichText::new(name).strong());
+        egui::ScrollArea::both()
+            .id_salt(("failure_output", name))
+            .max_height(160.0)
+            .auto_shrink([false, true])
+            .show(ui, |ui| {
+                // A `&str` buffer can be selected and copied but not edited
+                let mut text = output;
+                ui.add(
+                    egui::TextEdit::multiline(&mut text)
+                        .code_editor()
+                        .desired_width(f32::INFINITY),
+                );
+            });
+    }
+
+    /// Ask before an in-place conversion overwrites the input files
+    fn render_in_place_confirmation(&mut self, ctx: &EguiContext) {
+        if !self.confirm_in_place {
+            return;
+        }
+
+        // Modal, so the file list can't change while the user decides
+        let response = egui::Modal::new(egui::Id::new("confirm_in_place")).show(ctx, |ui| {
+            ui.heading("Convert in place?");
+            ui.label(format!(
+                "This replaces {} input files with their converted versions.",
+                self.input_paths.len()
+            ));
+            ui.label(
+                "The originals, and any files the outputs overwrite, are backed up first \
+                     and can be restored with Undo, also after restarting.",
+            );
+            ui.add_space(10.0);
+            ui.horizontal(|ui| {
+                if ui.button("Replace Originals").clicked() {
+                    self.confirm_in_place = false;
+                    self.start_conversion();
+                }
+                if ui.button("Cancel").clicked() {
+                    self.confirm_in_place = false;
+                }
+            });
+        });
+        if response.should_close() {
+            self.confirm_in_place = false;
+        }
+    }
+
+    fn render_undo_in_place(&mut self, ui: &mut Ui) {
+        if self.replaced_files.is_empty() {
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            let backup_dir = self.replaced_files[0]
+                .backup
+                .parent()
+                .map(|dir| dir.to_string_lossy().to_string())
+                .unwrap_or_default();
+            ui.label(format!(
+                "{} files replaced in place (backups in {})",
+                self.replaced_files.len(),
+                backup_dir
+            ));
+            if ui.button("↩ Undo").clicked() {
+                self.conversion_status = match in_place::undo(&mut self.replaced_files) {
+                    Ok(restored) => ConversionStatus::Completed {
+                        message: format!("Restored {} original files", restored),
+                    },
+                    Err(e) => ConversionStatus::Error {
+                        message: format!("{:#}", e),
+                    },
+                };
+                self.progress_rx = None;
+                self.cancel_tx = None;
+            }
+            if ui
+                .button("Keep Changes")
+                .on_hover_text("Delete the backups; the conversion can't be undone afterwards")
+                .clicked()
+            {
+                if let Err(e) = in_place::discard_backups(&mut self.replaced_files) {
+                    self.conversion_status = ConversionStatus::Error {
+                        message: format!("{:#}", e),
+                    };
+                }
+            }
+        });
+    }
+
+    /// Extra switches for hkxcmd, hkxconv and HavokBehaviorPostProcess, saved with the settings
+    fn render_tool_options(&mut self, ui: &mut Ui) {
+        if matches!(
+            self.converter_tool,
+            ConverterTool::HkxC | ConverterTool::Hct
+        ) {
+            return;
+        }
+        let mut changed = false;
 
-                    // Reset to a valid filter if current selection is not available
-                    if (self.converter_tool == ConverterTool::HkxC
-                        || self.converter_tool == ConverterTool::HkxConv)
-                        && self.input_file_extension == InputFileExtension::Kf
-                    {
-                        self.input_file_extension = InputFileExtension::Hkx;
-                    }
-                });
-                ui.end_row();
+        ui.vertical(|ui| {
+            changed |= self.render_tool_presets(ui);
 
-                ui.label("Input Files:");
-                ui.vertical(|ui| {
+            let output_format = self.output_format;
+            let options = &mut self.settings.tool_options;
+            let extra_args = match self.converter_tool {
+                ConverterTool::HkxCmd => {
+                    let hkxcmd = &mut options.hkxcmd;
                     ui.horizontal(|ui| {
-                        if ui.button("Browse Files").clicked() {
-                            if let Some(paths) = FileDialog::new().pick_files() {
-                                self.input_paths = paths;
-                                self.update_output_folder();
-                            }
-                        }
-                        if ui.button("Select Folder").clicked() {
-                            if let Some(folder) = FileDialog::new().pick_folder() {
-                                if let Err(e) = self.add_files_from_folder(&folder, false) {
-                                    eprintln!("Error adding files from folder: {}", e);
-                                }
-                                self.update_output_folder();
-                            }
-                        }
-                        if ui.button("Select Folder (+ Subfolders)").clicked() {
-                            if let Some(folder) = FileDialog::new().pick_folder() {
-                                if let Err(e) = self.add_files_from_folder(&folder, true) {
-                                    eprintln!("Error adding files from folders: {}", e);
+                        ui.label("Debug level:");
+                        egui::ComboBox::from_id_salt("hkxcmd_debug_level")
+                            .selected_text(hkxcmd.debug_level.map_or("Default", |l| l.as_str()))
+                            .show_ui(ui, |ui| {
+                                changed |= ui
+                                    .selectable_value(&mut hkxcmd.debug_level, None, "Default")
+                                    .changed();
+                                for level in DebugLevel::ALL {
+                                    changed |= ui
+                                        .selectable_value(
+                                            &mut hkxcmd.debug_level,
+                                            Some(level),
+                                            level.as_str(),
+                                        )
+                                        .changed();
                                 }
-                                self.update_output_folder();
-                            }
-                        }
+                            });
                     });
-                });
-                ui.end_row();
 
-                // Skeleton file selection (only show for animation conversion modes)
-                if self.conversion_mode.requires_skeleton() {
-                    ui.label("Skeleton File:");
-                    ui.horizontal(|ui| {
-                        if let Some(ref skeleton_file) = self.skeleton_file {
-                            ui.label(
-                                skeleton_file
-                                    .file_name()
-                                    .unwrap_or_default()
-                                    .to_string_lossy(),
+                    if self.conversion_mode == ConversionMode::HkxToKf {
+                        let export_kf = &mut hkxcmd.export_kf;
+                        ui.horizontal(|ui| {
+                            ui.label("NIF version (-v:):");
+                            changed |= ui
+                                .add(
+                                    egui::TextEdit::singleline(&mut export_kf.nif_version)
+                                        .hint_text("20.2.0.7")
+                                        .desired_width(80.0),
+                                )
+                                .changed();
+                            for (label, version) in [
+                                ("User version (-u:):", &mut export_kf.user_version),
+                                ("User version 2 (-u2:):", &mut export_kf.user_version2),
+                            ] {
+                                ui.label(label);
+                                let mut text = version.map(|v| v.to_string()).unwrap_or_default();
+                                if ui
+                                    .add(
+                                        egui::TextEdit::singleline(&mut text)
+                                            .hint_text("default")
+                                            .desired_width(50.0),
+                                    )
+                                    .changed()
+                                {
+                                    *version = text.trim().parse().ok();
+                                    changed = true;
+                                }
+                            }
+                        });
+                    } else {
+                        ui.horizontal(|ui| {
+                            ui.label("Platform (-v:):");
+                            let automatic = format!(
+                                "Output format ({})",
+                                HavokPlatform::for_format(output_format).as_str()
                             );
-                        }
-                        // else {
-                        //     ui.label("(required for animation conversion)");
-                        // }
-                        if ui.button("Browse").clicked() {
-                            if let Some(file) = FileDialog::new()
-                                .add_filter("HKX files", &["hkx"])
-                                .pick_file()
-                            {
-                                self.skeleton_file = Some(file);
+                            egui::ComboBox::from_id_salt("hkxcmd_platform")
+                                .selected_text(
+                                    hkxcmd
+                                        .platform
+                                        .map_or(automatic.clone(), |p| p.as_str().to_string()),
+                                )
+                                .show_ui(ui, |ui| {
+                                    changed |= ui
+                                        .selectable_value(&mut hkxcmd.platform, None, automatic)
+                                        .changed();
+                                    for platform in HavokPlatform::ALL {
+                                        changed |= ui
+                                            .selectable_value(
+                                                &mut hkxcmd.platform,
+                                                Some(platform),
+                                                platform.as_str(),
+                                            )
+                                            .changed();
+                                    }
+                                });
+                        });
+                        // ExportKF writes Gamebryo KF, which has no Havok save flags
+                        ui.horizontal_wrapped(|ui| {
+                            ui.label("Save flags:");
+                            for (name, enabled) in hkxcmd.save_flags.flags_mut() {
+                                changed |= ui.checkbox(enabled, name).changed();
                             }
-                        }
-                        if self.skeleton_file.is_some() && ui.button("Clear").clicked() {
-                            self.skeleton_file = None;
-                        }
+                        });
+                    }
+                    &mut hkxcmd.extra_args
+                }
+                ConverterTool::HkxConv => {
+                    let hkxconv = &mut options.hkxconv;
+                    ui.horizontal(|ui| {
+                        ui.label("Format (-v):");
+                        let automatic = format!(
+                            "Output format ({})",
+                            HkxConvFormat::for_format(output_format).as_str()
+                        );
+                        egui::ComboBox::from_id_salt("hkxconv_format")
+                            .selected_text(
+                                hkxconv
+                                    .format
+                                    .map_or(automatic.clone(), |f| f.as_str().to_string()),
+                            )
+                            .show_ui(ui, |ui| {
+                                changed |= ui
+                                    .selectable_value(&mut hkxconv.format, None, automatic)
+                                    .changed();
+                                for format in HkxConvFormat::ALL {
+                                    changed |= ui
+                                        .selectable_value(
+                                            &mut hkxconv.format,
+                                            Some(format),
+                                            format.as_str(),
+                                        )
+                                        .changed();
+                                }
+                            });
                     });
-                    ui.end_row();
+                    &mut hkxconv.extra_args
                 }
+                ConverterTool::HavokBehaviorPostProcess => {
+                    let post_process = &mut options.post_process;
+                    changed |= ui
+                        .checkbox(
+                            &mut post_process.platform_amd64,
+                            "--platformAmd64 (write 64-bit SE)",
+                        )
+                        .changed();
+                    &mut post_process.extra_args
+                }
+                ConverterTool::HkxC | ConverterTool::Hct => return,
+            };
 
-                ui.label("Output Folder:");
-                self.render_output_folder(ui);
-                ui.end_row();
-
-                ui.label("Output Suffix:");
-                ui.text_edit_singleline(&mut self.output_suffix);
-                ui.end_row();
-
-                ui.label("Custom Extension:");
-                ui.horizontal(|ui| {
-                    let mut extension_text =
-                        self.custom_extension.as_ref().cloned().unwrap_or_default();
-                    if ui.text_edit_singleline(&mut extension_text).changed() {
-                        self.custom_extension = if extension_text.is_empty() {
-                            None
-                        } else {
-                            Some(extension_text)
-                        };
-                    }
-                    // ui.label("(optional - leave empty to use format default)");
-                });
-                ui.end_row();
-
-                ui.label("Output Format:");
-                self.render_output_format(ui);
-                ui.end_row();
+            ui.horizontal(|ui| {
+                ui.label("Extra arguments:");
+                changed |= ui
+                    .add(
+                        egui::TextEdit::singleline(extra_args)
+                            .hint_text("passed before the file paths"),
+                    )
+                    .changed();
             });
+        });
 
-        ui.add_space(10.0);
-
-        // Selected Files section outside the grid for more space
-        ui.horizontal(|ui| {
-            ui.label("Selected Files:");
-            ui.label(format!("{} files selected", self.input_paths.len()));
-            if ui.button("Clear All").clicked() {
-                self.input_paths.clear();
+        if changed {
+            if let Err(e) = self.settings.save() {
+                eprintln!("Failed to save settings: {:#}", e);
             }
-        });
+        }
+    }
 
-        // Show drag and drop hint
+    /// Load, save and delete named tool option presets; returns whether options were loaded
+    fn render_tool_presets(&mut self, ui: &mut Ui) -> bool {
+        let mut loaded = false;
         ui.horizontal(|ui| {
-            ui.label(
-                RichText::new(
-                    "💡 Tip: You can drag and drop files or folders directly onto this window",
-                )
-                .color(Color32::from_rgb(100, 100, 100))
-                .size(12.0),
+            ui.label("Preset:");
+            egui::ComboBox::from_id_salt("tool_preset")
+                .selected_text("Load...")
+                .show_ui(ui, |ui| {
+                    for name in &self.tool_presets {
+                        if ui.selectable_label(false, name).clicked() {
+                            match settings::load_preset(name) {
+                                Ok(options) => {
+                                    self.settings.tool_options = options;
+                                    self.preset_name = name.clone();
+                                    loaded = true;
+                                }
+                                Err(e) => eprintln!("Failed to load preset: {:#}", e),
+                            }
+                        }
+                    }
+                });
+            ui.add(
+                egui::TextEdit::singleline(&mut self.preset_name)
+                    .hint_text("preset name")
+                    .desired_width(120.0),
             );
+            let name = self.preset_name.trim().to_string();
+            if ui
+                .add_enabled(!name.is_empty(), egui::Button::new("Save"))
+                .on_hover_text("Save the options of every tool under this name")
+                .clicked()
+            {
+                if let Err(e) = settings::save_preset(&name, &self.settings.tool_options) {
+                    eprintln!("Failed to save preset: {:#}", e);
+                }
+                self.tool_presets = settings::preset_names();
+            }
+            if self.tool_presets.contains(&name) && ui.button("Delete").clicked() {
+                if let Err(e) = settings::delete_preset(&name) {
+                    eprintln!("Failed to delete preset: {:#}", e);
+                }
+                self.tool_presets = settings::preset_names();
+            }
         });
+        loaded
+    }
 
-        // Show HCT processing note
-        // if self.converter_tool == ConverterTool::Hct {
-        //     ui.horizontal(|ui| {
-        //         ui.label(RichText::new("ℹ️ HCT files use isolated temp directories for safe concurrent processing").color(Color32::from_rgb(100, 100, 100)).size(12.0));
-        //     });
-        // }
-
-        // Scrollable area for file list with maximum height
-        let scroll_area_height = 200.0;
-        let files_to_remove = ui
-            .allocate_ui_with_layout(
-                egui::Vec2::new(ui.available_width(), scroll_area_height),
-                egui::Layout::top_down(egui::Align::LEFT),
-                |ui| {
-                    egui::ScrollArea::vertical()
-                        .auto_shrink([false; 2])
-                        .show(ui, |ui| {
-                            let mut files_to_remove = Vec::new();
-                            for (index, path) in self.input_paths.iter().enumerate() {
-                                ui.horizontal(|ui| {
-                                    if ui.small_button("❌").clicked() {
-                                        files_to_remove.push(index);
-                                    }
-                                    ui.label(
-                                        path.file_name().unwrap_or_default().to_string_lossy(),
-                                    );
-                                });
-                            }
-                            files_to_remove
-                        })
-                        .inner
-                },
-            )
-            .inner;
+    /// The `.hko` options the HCT backend will run with
+    fn hct_options(&self) -> Result<HkoOptions> {
+        if !self.hct_use_custom_hko {
+            return Ok(self.hct_settings.to_options());
+        }
 
-        // Remove files after the ScrollArea
-        for index in files_to_remove.iter().rev() {
-            self.input_paths.remove(*index);
+        let custom = self
+            .hct_custom_hko
+            .as_ref()
+            .context("No custom .hko file selected")?;
+        let configuration = custom
+            .options
+            .active()
+            .context("Selected configuration does not exist")?;
+        if configuration.output_filename().is_none() {
+            anyhow::bail!(
+                "Configuration \"{}\" has no \"Write to Platform\" filter",
+                configuration.name
+            );
         }
+        Ok(custom.options.clone())
+    }
 
-        ui.add_space(10.0);
+    fn render_hct_options(&mut self, ui: &mut Ui) {
+        ui.vertical(|ui| {
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut self.hct_use_custom_hko, false, "Generated options");
+                ui.radio_value(&mut self.hct_use_custom_hko, true, "Custom .hko file");
+            });
 
-        self.handle_conversion(ui);
+            if self.hct_use_custom_hko {
+                self.render_custom_hko(ui);
+                return;
+            }
+
+            let writer = &mut self.hct_settings.platform_writer;
+            ui.horizontal(|ui| {
+                ui.label("Write to Platform preset:");
+                egui::ComboBox::from_id_salt("hct_platform_preset")
+                    .selected_text(writer.preset.as_str())
+                    .show_ui(ui, |ui| {
+                        for preset in PlatformPreset::ALL {
+                            if ui
+                                .selectable_value(&mut writer.preset, preset, preset.as_str())
+                                .clicked()
+                            {
+                                // Keep the pointer size in line with the chosen preset
+                                if let Some(bytes_in_pointer) = preset.bytes_in_pointer() {
+                                    writer.bytes_in_pointer = bytes_in_pointer;
+                                }
+                            }
+                        }
+                    });
+            });
+            ui.horizontal(|ui| {
+                ui.label("Pointer size:");
+                ui.add_enabled_ui(writer.preset == PlatformPreset::Custom, |ui| {
+                    ui.radio_value(&mut writer.bytes_in_pointer, 4, "4 bytes");
+                    ui.radio_value(&mut writer.bytes_in_pointer, 8, "8 bytes");
+                });
+            });
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut writer.tagfile, "Tagfile");
+                ui.checkbox(&mut writer.xml_format, "XML format");
+                ui.checkbox(&mut writer.remove_metadata, "Remove metadata");
+            });
+            // The output is named and checked by the format these options write
+            if let Some(format) = writer.output_format() {
+                if format != self.output_format {
+                    writer.filename = format!("filename.{}", format.extension());
+                    self.output_format = format;
+                }
+            }
+
+            egui::CollapsingHeader::new("Prune Types")
+                .id_salt("hct_prune_types")
+                .show(ui, |ui| {
+                    egui::Grid::new("hct_prune_types_grid")
+                        .num_columns(2)
+                        .show(ui, |ui| {
+                            for (index, (name, flag)) in self
+                                .hct_settings
+                                .prune_types
+                                .flags_mut()
+                                .into_iter()
+                                .enumerate()
+                            {
+                                ui.checkbox(flag, name);
+                                if index % 2 == 1 {
+                                    ui.end_row();
+                                }
+                            }
+                        });
+                });
+        });
     }
 
-    fn render_output_folder(&mut self, ui: &mut Ui) {
+    fn render_custom_hko(&mut self, ui: &mut Ui) {
         ui.horizontal(|ui| {
-            if let Some(ref output_folder) = self.output_folder {
-                ui.label(output_folder.to_string_lossy());
+            if let Some(custom) = &self.hct_custom_hko {
+                ui.label(
+                    custom
+                        .path
+                        .file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy(),
+                );
             }
             if ui.button("Browse").clicked() {
-                if let Some(folder) = FileDialog::new().pick_folder() {
-                    self.output_folder = Some(folder);
+                if let Some(file) = FileDialog::new()
+                    .add_filter("HCT options", &["hko"])
+                    .pick_file()
+                {
+                    match CustomHko::load(file) {
+                        Ok(custom) => self.hct_custom_hko = Some(custom),
+                        Err(e) => {
+                            self.conversion_status = ConversionStatus::Error {
+                                message: format!("{:#}", e),
+                            };
+                        }
+                    }
                 }
             }
         });
+
+        let Some(custom) = &mut self.hct_custom_hko else {
+            return;
+        };
+
+        let options = &mut custom.options;
+        ui.horizontal(|ui| {
+            ui.label("Configuration:");
+            let selected = options
+                .active()
+                .map(|configuration| configuration.name.clone())
+                .unwrap_or_default();
+            egui::ComboBox::from_id_salt("hct_configuration")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    for (index, configuration) in options.configurations.iter().enumerate() {
+                        ui.selectable_value(
+                            &mut options.active_configuration,
+                            index,
+                            &configuration.name,
+                        );
+                    }
+                });
+        });
+
+        match options.active().and_then(|c| c.output_filename()) {
+            Some(filename) => {
+                ui.label(format!("Output file: {}", filename));
+            }
+            None => {
+                ui.label(
+                    RichText::new("This configuration has no \"Write to Platform\" filter")
+                        .color(Color32::from_rgb(255, 120, 120)),
+                );
+            }
+        }
+    }
+
+    /// Switch tools, resetting the mode, input filter and output format to ones it supports
+    fn select_converter_tool(&mut self, tool: ConverterTool) {
+        self.converter_tool = tool;
+        // Reset to regular mode if tool doesn't support KF conversion and we're in KF mode
+        if !tool.supports_kf_conversion() && self.conversion_mode != ConversionMode::Regular {
+            self.conversion_mode = ConversionMode::Regular;
+        }
+        // Reset input file extension if tool doesn't support current filter
+        if !tool
+            .available_input_extensions()
+            .contains(&self.input_file_extension)
+        {
+            self.input_file_extension = InputFileExtension::Hkx;
+        }
+        // Reset output format if tool doesn't support current format
+        let available_formats = self.available_output_formats_for_mode();
+        if !available_formats.contains(&self.output_format) && !available_formats.is_empty() {
+            self.output_format = available_formats[0];
+        }
+        self.sync_hct_output_format();
+    }
+
+    /// Point the generated "Write to Platform" options at the selected output format, or
+    /// the selected output format at what a custom `.hko` file writes
+    fn sync_hct_output_format(&mut self) {
+        if self.converter_tool != ConverterTool::Hct {
+            return;
+        }
+        if !self.hct_use_custom_hko {
+            self.hct_settings
+                .platform_writer
+                .set_output_format(self.output_format);
+            return;
+        }
+        let custom_format = self
+            .hct_custom_hko
+            .as_ref()
+            .and_then(|custom| custom.options.active())
+            .and_then(|configuration| configuration.output_format());
+        if let Some(format) = custom_format {
+            self.output_format = format;
+        }
     }
 
     fn render_output_format(&mut self, ui: &mut Ui) {
+        let previous_format = self.output_format;
         ui.horizontal(|ui| {
             let available_formats = self.available_output_formats_for_mode();
 
@@ -1618,13 +4132,120 @@ impl HkxToolsApp {
                 self.input_file_extension = InputFileExtension::Hkx;
             }
         });
+
+        if self.output_format != previous_format {
+            self.sync_hct_output_format();
+        }
+    }
+
+    fn render_file_diagnostics(&self, ui: &mut Ui) {
+        if self.file_diagnostics.is_empty() {
+            return;
+        }
+
+        egui::CollapsingHeader::new(format!(
+            "Diagnostics ({} files)",
+            self.file_diagnostics.len()
+        ))
+        .id_salt("file_diagnostics")
+        .show(ui, |ui| {
+            egui::ScrollArea::vertical()
+                .max_height(150.0)
+                .show(ui, |ui| {
+                    for (file_name, file) in &self.file_diagnostics {
+                        ui.label(RichText::new(file_name).strong());
+                        if let Some(temp_dir) = &file.kept_temp_dir {
+                            ui.horizontal(|ui| {
+                                ui.label("HCT files kept in:");
+                                ui.label(RichText::new(temp_dir.to_string_lossy()).monospace());
+                            });
+                        }
+                        for diagnostic in &file.diagnostics {
+                            let (tag, color) = match diagnostic.severity {
+                                hct::Severity::Error => ("error", Color32::from_rgb(255, 120, 120)),
+                                hct::Severity::Warning => {
+                                    ("warning", Color32::from_rgb(230, 180, 80))
+                                }
+                            };
+                            ui.horizontal_wrapped(|ui| {
+                                ui.label(RichText::new(tag).color(color).strong());
+                                if let Some(filter) = &diagnostic.filter {
+                                    ui.label(RichText::new(format!("[{}]", filter)).strong());
+                                }
+                                ui.label(&diagnostic.message);
+                            });
+                        }
+                        ui.add_space(4.0);
+                    }
+                });
+        });
+    }
+
+    /// Files a finished run never got to were skipped. A run stopped by a failed file sends
+    /// no completion, so its status is settled here.
+    fn finish_file_updates(&mut self) {
+        for update in self.file_updates.values_mut() {
+            if matches!(update.state, FileState::Queued | FileState::Running) {
+                update.state = FileState::Skipped;
+            }
+        }
+        if matches!(self.conversion_status, ConversionStatus::Running { .. }) {
+            let failed = self
+                .file_updates
+                .values()
+                .filter(|update| update.state == FileState::Failed)
+                .count();
+            self.conversion_status = ConversionStatus::Error {
+                message: if failed > 0 {
+                    format!(
+                        "{} of {} files failed to convert",
+                        failed,
+                        self.file_updates.len()
+                    )
+                } else {
+                    "Conversion stopped before every file was converted".to_string()
+                },
+            };
+        }
+        let unavailable = self
+            .file_updates
+            .values()
+            .filter(|update| update.state == FileState::Unavailable)
+            .count();
+        if let ConversionStatus::Completed { message } = &mut self.conversion_status {
+            if unavailable > 0 {
+                message.push_str(&format!(
+                    "; {} files were left out because their converter can't handle them",
+                    unavailable
+                ));
+            }
+        }
     }
 
     fn handle_conversion(&mut self, ui: &mut Ui) {
         // Check for progress updates
         if let Some(progress_rx) = &mut self.progress_rx {
-            while let Ok(progress) = progress_rx.try_recv() {
+            loop {
+                let progress = match progress_rx.try_recv() {
+                    Ok(progress) => progress,
+                    Err(mpsc::error::TryRecvError::Empty) => break,
+                    Err(mpsc::error::TryRecvError::Disconnected) => {
+                        self.finish_file_updates();
+                        self.progress_rx = None;
+                        break;
+                    }
+                };
                 self.conversion_status = progress.status;
+                if let Some(replaced) = progress.replaced {
+                    self.replaced_files.push(replaced);
+                }
+                if let Some(diagnostics) = progress.diagnostics {
+                    self.file_diagnostics
+                        .push((progress.current_file, diagnostics));
+                }
+                if let Some(file) = progress.file {
+                    self.file_updates.insert(file.input.clone(), file);
+                }
                 // Request repaint to update UI immediately
                 ui.ctx().request_repaint();
             }
@@ -1692,6 +4313,11 @@ impl HkxToolsApp {
             }
         }
 
+        self.render_file_diagnostics(ui);
+        if !matches!(current_status, ConversionStatus::Running { .. }) {
+            self.render_undo_in_place(ui);
+        }
+
         ui.add_space(10.0);
 
         // Big prominent button at the bottom
@@ -1703,7 +4329,11 @@ impl HkxToolsApp {
                         .fill(Color32::from_rgb(70, 130, 220));
 
                 if ui.add(button).clicked() {
-                    self.start_conversion();
+                    if self.in_place {
+                        self.confirm_in_place = true;
+                    } else {
+                        self.start_conversion();
+                    }
                 }
             }
             ConversionStatus::Running { .. } => {
@@ -1760,12 +4390,39 @@ impl eframe::App for HkxToolsApp {
         // Handle drag and drop files
         if !ctx.input(|i| i.raw.dropped_files.is_empty()) {
             let dropped_files = ctx.input(|i| i.raw.dropped_files.clone());
-            self.handle_dropped_files(dropped_files);
+            match self.active_tab {
+                AppTab::Converter | AppTab::NemesisPatch | AppTab::Settings => {
+                    self.handle_dropped_files(dropped_files)
+                }
+                // The inspector shows one file at a time, so open the first one dropped
+                AppTab::Inspector => {
+                    if let Some(path) = dropped_files.into_iter().find_map(|file| file.path) {
+                        self.inspector.open(path);
+                    }
+                }
+                AppTab::Annotations => {
+                    if let Some(path) = dropped_files.into_iter().find_map(|file| file.path) {
+                        self.annotations_tab.open(path);
+                    }
+                }
+                // A dropped folder is scanned
+                AppTab::AnimationReport => {
+                    if let Some(path) = dropped_files
+                        .into_iter()
+                        .find_map(|file| file.path.filter(|path| path.is_dir()))
+                    {
+                        self.report_tab.scan(path);
+                    }
+                }
+            }
         }
 
         egui::CentralPanel::default().show(ctx, |ui| {
             self.render_main_ui(ui);
         });
+        self.render_in_place_confirmation(ctx);
+        self.render_failure_detail(ctx);
+        self.render_bsa_browser(ctx);
 
         // Show drag and drop overlay when files are being hovered
         if files_being_hovered {
@@ -1779,7 +4436,7 @@ async fn main() -> Result<(), eframe::Error> {
     // Create a tokio runtime handle for the GUI
     let tokio_handle = tokio::runtime::Handle::current();
 
-    // Write hkxcmd.exe, hkxc.exe, hkxconv.exe, and HCT .hko file to a temporary location
+    // Write hkxcmd.exe, hkxconv.exe, and HavokBehaviorPostProcess.exe to a temporary location
     let temp_dir = tempfile::Builder::new()
         .prefix("hkxtools_")
         .tempdir()
@@ -1787,12 +4444,10 @@ async fn main() -> Result<(), eframe::Error> {
 
     let hkxcmd_path = temp_dir.path().join("hkxcmd.exe");
     let hkxconv_path = temp_dir.path().join("hkxconv.exe");
-    let sse_to_le_hko_path = temp_dir.path().join("_SSEtoLE.hko");
     let havok_behavior_post_process_path = temp_dir.path().join("HavokBehaviorPostProcess.exe");
 
     fs::write(&hkxcmd_path, HKXCMD_EXE).unwrap();
     fs::write(&hkxconv_path, HKXCONV_EXE).unwrap();
-    fs::write(&sse_to_le_hko_path, SSE_TO_LE_HKO).unwrap();
     fs::write(
         &havok_behavior_post_process_path,
         HAVOK_BEHAVIOR_POST_PROCESS_EXE,
@@ -1801,12 +4456,16 @@ async fn main() -> Result<(), eframe::Error> {
 
     println!("Extracted hkxcmd.exe to: {:?}", hkxcmd_path);
     println!("Extracted hkxconv.exe to: {:?}", hkxconv_path);
-    println!("Extracted _SSEtoLE.hko to: {:?}", sse_to_le_hko_path);
     println!(
         "Extracted HavokBehaviorPostProcess.exe to: {:?}",
         havok_behavior_post_process_path
     );
-    println!("HCT will be called from PATH as: hctStandAloneFilterManager.exe");
+
+    let settings = Settings::load();
+    println!(
+        "HCT: {}",
+        HctLookup::locate(settings.hct_path.as_deref()).explanation()
+    );
 
     // Window width and height
     let options = eframe::NativeOptions {
@@ -1824,8 +4483,8 @@ async fn main() -> Result<(), eframe::Error> {
             Ok(Box::new(HkxToolsApp::new(
                 hkxcmd_path,
                 hkxconv_path,
-                sse_to_le_hko_path,
                 havok_behavior_post_process_path,
+                settings,
                 tokio_handle,
             )))
         }),
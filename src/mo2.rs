@@ -0,0 +1,142 @@
+//! Mod Organizer 2 instances: the mods of a profile as conversion inputs, and a mod folder
+//! as the output with the game's data layout (`meshes/...`) kept.
+
+use anyhow::{Context as AnyhowContext, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A mod from a profile's `modlist.txt`
+#[derive(Debug, Clone)]
+pub struct Mo2Mod {
+    pub name: String,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Mo2Instance {
+    pub ini_path: PathBuf,
+    pub mods_dir: PathBuf,
+    pub profiles_dir: PathBuf,
+    pub profiles: Vec<String>,
+    pub profile: String,
+    /// Mods of `profile`, highest priority first as in the MO2 left pane
+    pub mods: Vec<Mo2Mod>,
+}
+
+impl Mo2Instance {
+    /// Read an instance from its `ModOrganizer.ini` and load the selected profile's mod list
+    pub fn open(ini_path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(ini_path)
+            .with_context(|| format!("Failed to read {:?}", ini_path))?;
+        let instance_dir = ini_path.parent().unwrap_or(Path::new("")).to_path_buf();
+
+        let base_dir = ini_value(&text, "Settings", "base_directory")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| instance_dir.clone());
+        let directory = |key: &str, default: &str| {
+            ini_value(&text, "Settings", key)
+                .map(|value| {
+                    PathBuf::from(value.replace("%BASE_DIR%", &base_dir.to_string_lossy()))
+                })
+                .unwrap_or_else(|| base_dir.join(default))
+        };
+        let mods_dir = directory("mod_directory", "mods");
+        let profiles_dir = directory("profiles_directory", "profiles");
+
+        let mut profiles = fs::read_dir(&profiles_dir)
+            .with_context(|| format!("Failed to read profiles folder {:?}", profiles_dir))?
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect::<Vec<_>>();
+        profiles.sort_by_key(|profile| profile.to_lowercase());
+
+        let profile = ini_value(&text, "General", "selected_profile")
+            .filter(|profile| profiles.contains(profile))
+            .or_else(|| profiles.first().cloned())
+            .context("The instance has no profiles")?;
+
+        let mut instance = Self {
+            ini_path: ini_path.to_path_buf(),
+            mods_dir,
+            profiles_dir,
+            profiles,
+            profile: String::new(),
+            mods: Vec::new(),
+        };
+        instance.select_profile(&profile)?;
+        Ok(instance)
+    }
+
+    pub fn select_profile(&mut self, profile: &str) -> Result<()> {
+        let modlist = self.profiles_dir.join(profile).join("modlist.txt");
+        let text = fs::read_to_string(&modlist)
+            .with_context(|| format!("Failed to read {:?}", modlist))?;
+        self.mods = parse_modlist(&text);
+        self.profile = profile.to_string();
+        Ok(())
+    }
+
+    pub fn mod_folder(&self, name: &str) -> PathBuf {
+        self.mods_dir.join(name)
+    }
+
+    /// Folder of `file` inside its mod, e.g. `meshes/actors/dragon/behaviors`, if it is in one
+    pub fn folder_in_mod(&self, file: &Path) -> Option<PathBuf> {
+        let in_mods = file.parent()?.strip_prefix(&self.mods_dir).ok()?;
+        Some(in_mods.components().skip(1).collect())
+    }
+}
+
+/// Mods listed in `modlist.txt`: `+` enabled, `-` disabled. Unmanaged entries (`*`, DLCs and
+/// the base game) and separators have no folder to read from, so they are left out.
+fn parse_modlist(text: &str) -> Vec<Mo2Mod> {
+    text.lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let (enabled, name) = if let Some(name) = line.strip_prefix('+') {
+                (true, name)
+            } else if let Some(name) = line.strip_prefix('-') {
+                (false, name)
+            } else {
+                return None;
+            };
+            if name.ends_with("_separator") {
+                return None;
+            }
+            Some(Mo2Mod {
+                name: name.to_string(),
+                enabled,
+            })
+        })
+        .collect()
+}
+
+/// Look up `key` in `[section]`, unwrapping Qt's `@ByteArray(...)` and escaped backslashes
+fn ini_value(text: &str, section: &str, key: &str) -> Option<String> {
+    let mut in_section = false;
+    for line in text.lines() {
+        let line = line.trim();
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            in_section = name.eq_ignore_ascii_case(section);
+            continue;
+        }
+        if !in_section {
+            continue;
+        }
+        let Some((name, value)) = line.split_once('=') else {
+            continue;
+        };
+        if name.trim() != key {
+            continue;
+        }
+        let value = value.trim();
+        let value = value
+            .strip_prefix("@ByteArray(")
+            .and_then(|v| v.strip_suffix(')'))
+            .unwrap_or(value)
+            .replace("\\\\", "\\");
+        return (!value.is_empty()).then_some(value);
+    }
+    None
+}
@@ -0,0 +1,271 @@
+//! Nemesis/Pandora patches: the difference between a vanilla behavior and a modified one,
+//! written as the node files of a Nemesis mod (`Nemesis_Engine/mod/<code>/<behavior>/`).
+//!
+//! Both files are read through serde_hkx, so either can be LE, SE or XML. Nodes that only
+//! exist in the modified file become `#<code>$N` nodes; changed nodes are written in full
+//! with each edit wrapped in `MOD_CODE ~<code>~ OPEN` / `ORIGINAL` / `CLOSE` markers.
+
+use crate::hkx_document::{HkxDocument, HkxObject, HkxValue};
+use anyhow::{bail, Context as AnyhowContext, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Packfile objects are written two levels deep (`hkpackfile` > `hksection`)
+const OBJECT_DEPTH: usize = 2;
+
+#[derive(Debug, Clone, Default)]
+pub struct PatchOptions {
+    /// Short id Nemesis uses for the mod, e.g. `abcd`
+    pub mod_code: String,
+    pub mod_name: String,
+    pub author: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct PatchSummary {
+    pub folder: PathBuf,
+    pub changed: usize,
+    pub added: usize,
+    /// Vanilla nodes missing from the modified file; Nemesis has no way to remove nodes
+    pub removed: usize,
+}
+
+/// Compare `vanilla` with `modified` and write the patch files below `output`
+pub async fn generate(
+    vanilla: &Path,
+    modified: &Path,
+    output: &Path,
+    options: &PatchOptions,
+) -> Result<PatchSummary> {
+    let code = options.mod_code.trim();
+    if code.is_empty() || !code.chars().all(|c| c.is_ascii_alphanumeric()) {
+        bail!("The mod code must be letters and digits only, e.g. \"abcd\"");
+    }
+
+    let vanilla_document = HkxDocument::load(vanilla).await?;
+    let modified_document = HkxDocument::load(modified).await?;
+
+    // Nodes the mod adds get Nemesis ids, and every reference to them follows
+    let mut new_names = HashMap::new();
+    for object in &modified_document.objects {
+        let Some(name) = &object.name else {
+            continue;
+        };
+        if vanilla_document.object(name).is_none() {
+            new_names.insert(name.clone(), format!("#{}${}", code, new_names.len()));
+        }
+    }
+
+    let behavior = vanilla
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_lowercase();
+    let mod_folder = output.join("Nemesis_Engine").join("mod").join(code);
+    let folder = mod_folder.join(&behavior);
+    fs::create_dir_all(&folder).with_context(|| format!("Failed to create {:?}", folder))?;
+
+    let mut summary = PatchSummary {
+        folder: folder.clone(),
+        changed: 0,
+        added: 0,
+        removed: vanilla_document
+            .objects
+            .iter()
+            .filter_map(|object| object.name.as_ref())
+            .filter(|name| modified_document.object(name).is_none())
+            .count(),
+    };
+
+    for object in &modified_document.objects {
+        let Some(name) = &object.name else {
+            continue;
+        };
+        let mut object = object.clone();
+        rename_pointers(&mut object, &new_names);
+        let modified_xml = object.to_xml_at(OBJECT_DEPTH);
+
+        let (file_name, text) = match new_names.get(name) {
+            Some(new_name) => {
+                summary.added += 1;
+                (new_name.clone(), modified_xml)
+            }
+            None => {
+                let vanilla_xml = vanilla_document
+                    .object(name)
+                    .map(|object| object.to_xml_at(OBJECT_DEPTH))
+                    .unwrap_or_default();
+                let Some(text) = patch_text(&vanilla_xml, &modified_xml, code) else {
+                    continue;
+                };
+                summary.changed += 1;
+                (name.clone(), text)
+            }
+        };
+        let path = folder.join(format!("{}.txt", file_name));
+        fs::write(&path, text).with_context(|| format!("Failed to write {:?}", path))?;
+    }
+
+    let info = format!(
+        "name={}\nauthor={}\nsite=\nauto=\n",
+        options.mod_name.trim(),
+        options.author.trim()
+    );
+    fs::write(mod_folder.join("info.ini"), info).context("Failed to write info.ini")?;
+
+    Ok(summary)
+}
+
+/// Point references (and the object's own name) at the Nemesis ids of new nodes
+fn rename_pointers(object: &mut HkxObject, new_names: &HashMap<String, String>) {
+    if let Some(new_name) = object.name.as_ref().and_then(|name| new_names.get(name)) {
+        object.name = Some(new_name.clone());
+    }
+    for param in &mut object.params {
+        match &mut param.value {
+            HkxValue::Text(text) => {
+                // Keep the original whitespace so untouched lines still match vanilla
+                *text = text
+                    .split_inclusive(char::is_whitespace)
+                    .map(|piece| {
+                        let token = piece.trim_end();
+                        match new_names.get(token) {
+                            Some(new_name) => format!("{}{}", new_name, &piece[token.len()..]),
+                            None => piece.to_string(),
+                        }
+                    })
+                    .collect();
+            }
+            HkxValue::Strings(_) => {}
+            HkxValue::Objects(members) => {
+                for member in members {
+                    rename_pointers(member, new_names);
+                }
+            }
+        }
+    }
+}
+
+enum LineChange<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Nemesis markup of a changed node, or `None` if nothing changed. Lines that only differ
+/// in `numelements` count as unchanged, since Nemesis recounts arrays itself.
+fn patch_text(vanilla: &str, modified: &str, code: &str) -> Option<String> {
+    let old = vanilla.lines().collect::<Vec<_>>();
+    let new = modified.lines().collect::<Vec<_>>();
+    let changes = diff_lines(&old, &new);
+    if changes
+        .iter()
+        .all(|change| matches!(change, LineChange::Same(_)))
+    {
+        return None;
+    }
+
+    let mut out = String::new();
+    let mut removed = Vec::new();
+    let mut added = Vec::new();
+    let flush = |out: &mut String, removed: &mut Vec<&str>, added: &mut Vec<&str>| {
+        if removed.is_empty() && added.is_empty() {
+            return;
+        }
+        out.push_str(&format!("<!-- MOD_CODE ~{}~ OPEN -->\n", code));
+        for line in added.drain(..) {
+            out.push_str(line);
+            out.push('\n');
+        }
+        if !removed.is_empty() {
+            out.push_str("<!-- ORIGINAL -->\n");
+            for line in removed.drain(..) {
+                out.push_str(line);
+                out.push('\n');
+            }
+        }
+        out.push_str("<!-- CLOSE -->\n");
+    };
+    for change in changes {
+        match change {
+            LineChange::Same(line) => {
+                flush(&mut out, &mut removed, &mut added);
+                out.push_str(line);
+                out.push('\n');
+            }
+            LineChange::Removed(line) => removed.push(line),
+            LineChange::Added(line) => added.push(line),
+        }
+    }
+    flush(&mut out, &mut removed, &mut added);
+    Some(out)
+}
+
+/// Longest-common-subsequence line diff; common leading and trailing lines are matched
+/// first so the table only covers the edited middle
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<LineChange<'a>> {
+    let same = |a: &str, b: &str| without_num_elements(a) == without_num_elements(b);
+
+    let prefix = old.iter().zip(new).take_while(|(a, b)| same(a, b)).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| same(a, b))
+        .count();
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+
+    // lengths[i][j]: LCS of old_middle[i..] and new_middle[j..]
+    let width = new_middle.len() + 1;
+    let mut lengths = vec![0u32; (old_middle.len() + 1) * width];
+    for i in (0..old_middle.len()).rev() {
+        for j in (0..new_middle.len()).rev() {
+            lengths[i * width + j] = if same(old_middle[i], new_middle[j]) {
+                lengths[(i + 1) * width + j + 1] + 1
+            } else {
+                lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+            };
+        }
+    }
+
+    // Unchanged lines keep the vanilla text, so numelements-only edits disappear
+    let mut changes = old[..prefix]
+        .iter()
+        .map(|line| LineChange::Same(line))
+        .collect::<Vec<_>>();
+    let (mut i, mut j) = (0, 0);
+    while i < old_middle.len() || j < new_middle.len() {
+        if i < old_middle.len() && j < new_middle.len() && same(old_middle[i], new_middle[j]) {
+            changes.push(LineChange::Same(old_middle[i]));
+            i += 1;
+            j += 1;
+        } else if j < new_middle.len()
+            && (i == old_middle.len() || lengths[i * width + j + 1] >= lengths[(i + 1) * width + j])
+        {
+            changes.push(LineChange::Added(new_middle[j]));
+            j += 1;
+        } else {
+            changes.push(LineChange::Removed(old_middle[i]));
+            i += 1;
+        }
+    }
+    changes.extend(
+        old[old.len() - suffix..]
+            .iter()
+            .map(|line| LineChange::Same(line)),
+    );
+    changes
+}
+
+fn without_num_elements(line: &str) -> String {
+    let Some(start) = line.find(" numelements=\"") else {
+        return line.to_string();
+    };
+    let value_start = start + " numelements=\"".len();
+    match line[value_start..].find('"') {
+        Some(end) => format!("{}{}", &line[..start], &line[value_start + end + 1..]),
+        None => line.to_string(),
+    }
+}
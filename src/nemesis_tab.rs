@@ -0,0 +1,186 @@
+//! Nemesis Patch tab: turn a modified behavior into Nemesis/Pandora patch files.
+
+use crate::nemesis::{self, PatchOptions, PatchSummary};
+use anyhow::Result;
+use eframe::egui;
+use egui::{Color32, RichText, Ui};
+use rfd::FileDialog;
+use std::path::PathBuf;
+use tokio::sync::oneshot;
+
+pub struct NemesisTab {
+    vanilla: Option<PathBuf>,
+    modified: Option<PathBuf>,
+    output_folder: Option<PathBuf>,
+    options: PatchOptions,
+    result: Option<std::result::Result<PatchSummary, String>>,
+    generate_rx: Option<oneshot::Receiver<Result<PatchSummary>>>,
+    tokio_handle: tokio::runtime::Handle,
+}
+
+impl NemesisTab {
+    pub fn new(tokio_handle: tokio::runtime::Handle) -> Self {
+        Self {
+            vanilla: None,
+            modified: None,
+            output_folder: None,
+            options: PatchOptions::default(),
+            result: None,
+            generate_rx: None,
+            tokio_handle,
+        }
+    }
+
+    fn poll_generate(&mut self, ui: &Ui) {
+        let Some(generate_rx) = &mut self.generate_rx else {
+            return;
+        };
+
+        match generate_rx.try_recv() {
+            Ok(result) => {
+                self.result = Some(result.map_err(|e| format!("{:#}", e)));
+                self.generate_rx = None;
+            }
+            Err(oneshot::error::TryRecvError::Empty) => ui.ctx().request_repaint(),
+            Err(oneshot::error::TryRecvError::Closed) => {
+                self.result = Some(Err("Generating patches was interrupted".to_string()));
+                self.generate_rx = None;
+            }
+        }
+    }
+
+    fn start(&mut self) {
+        let (Some(vanilla), Some(modified), Some(output_folder)) = (
+            self.vanilla.clone(),
+            self.modified.clone(),
+            self.output_folder.clone(),
+        ) else {
+            return;
+        };
+        let options = self.options.clone();
+        let (generate_tx, generate_rx) = oneshot::channel();
+        self.generate_rx = Some(generate_rx);
+        self.result = None;
+
+        self.tokio_handle.spawn(async move {
+            let _ = generate_tx
+                .send(nemesis::generate(&vanilla, &modified, &output_folder, &options).await);
+        });
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui) {
+        self.poll_generate(ui);
+
+        egui::Grid::new("nemesis_grid")
+            .num_columns(2)
+            .spacing([10.0, 8.0])
+            .show(ui, |ui| {
+                for (label, path) in [
+                    ("Vanilla Behavior:", &mut self.vanilla),
+                    ("Modified Behavior:", &mut self.modified),
+                ] {
+                    ui.label(label);
+                    ui.horizontal(|ui| {
+                        if let Some(path) = path.as_ref() {
+                            ui.label(path.to_string_lossy());
+                        }
+                        if ui.button("Browse").clicked() {
+                            if let Some(file) = FileDialog::new()
+                                .add_filter("HKX/XML files", &["hkx", "xml"])
+                                .pick_file()
+                            {
+                                *path = Some(file);
+                            }
+                        }
+                    });
+                    ui.end_row();
+                }
+
+                ui.label("Mod Code:");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.options.mod_code)
+                        .hint_text("e.g. abcd")
+                        .desired_width(80.0),
+                );
+                ui.end_row();
+
+                ui.label("Mod Name:");
+                ui.text_edit_singleline(&mut self.options.mod_name);
+                ui.end_row();
+
+                ui.label("Author:");
+                ui.text_edit_singleline(&mut self.options.author);
+                ui.end_row();
+
+                ui.label("Output Folder:");
+                ui.horizontal(|ui| {
+                    if let Some(folder) = &self.output_folder {
+                        ui.label(folder.to_string_lossy());
+                    }
+                    if ui.button("Browse").clicked() {
+                        if let Some(folder) = FileDialog::new().pick_folder() {
+                            self.output_folder = Some(folder);
+                        }
+                    }
+                });
+                ui.end_row();
+            });
+
+        ui.label(
+            RichText::new(
+                "Patches go to <output>/Nemesis_Engine/mod/<code>/<behavior>/, \
+                 which Nemesis and Pandora both read",
+            )
+            .color(Color32::from_rgb(100, 100, 100))
+            .size(12.0),
+        );
+        ui.add_space(10.0);
+
+        if self.generate_rx.is_some() {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label("Reading both behaviors through serde_hkx...");
+            });
+            return;
+        }
+
+        let ready = self.vanilla.is_some()
+            && self.modified.is_some()
+            && self.output_folder.is_some()
+            && !self.options.mod_code.trim().is_empty();
+        if ui
+            .add_enabled(ready, egui::Button::new("Generate Patches"))
+            .clicked()
+        {
+            self.start();
+        }
+
+        match &self.result {
+            Some(Ok(summary)) => {
+                ui.label(
+                    RichText::new(format!(
+                        "Wrote {} changed and {} new nodes to {}",
+                        summary.changed,
+                        summary.added,
+                        summary.folder.to_string_lossy()
+                    ))
+                    .color(Color32::from_rgb(100, 200, 100)),
+                );
+                if summary.removed > 0 {
+                    ui.label(
+                        RichText::new(format!(
+                            "{} nodes of the vanilla behavior are missing from the modified one; \
+                             Nemesis patches can't remove nodes, so unlink them instead",
+                            summary.removed
+                        ))
+                        .color(Color32::from_rgb(255, 200, 100)),
+                    );
+                }
+            }
+            Some(Err(error)) => {
+                ui.label(RichText::new(error).color(Color32::from_rgb(255, 120, 120)));
+            }
+            None => {}
+        }
+    }
+}
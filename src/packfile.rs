@@ -0,0 +1,210 @@
+//! Binary Havok packfile header parsing.
+//!
+//! Only the fixed header, section table and class fixups are read here; object data is left
+//! to serde_hkx.
+
+use anyhow::{bail, Context as AnyhowContext, Result};
+use std::collections::BTreeMap;
+
+pub const MAGIC: [u32; 2] = [0x57E0_E057, 0x10C0_C010];
+/// Contents version written by Skyrim's Havok build (both LE and SE)
+pub const SKYRIM_CONTENTS_VERSION: &str = "hk_2010.2.0-r1";
+
+const HEADER_SIZE: usize = 64;
+const SECTION_HEADER_SIZE: usize = 48;
+
+#[derive(Debug, Clone)]
+pub struct PackfileHeader {
+    pub user_tag: i32,
+    pub file_version: i32,
+    pub pointer_size: u8,
+    pub little_endian: bool,
+    pub reuse_padding_optimization: bool,
+    pub empty_base_class_optimization: bool,
+    pub contents_version: String,
+    pub sections: Vec<PackfileSection>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PackfileSection {
+    pub tag: String,
+    pub absolute_data_start: u32,
+    pub local_fixups_offset: u32,
+    pub global_fixups_offset: u32,
+    pub virtual_fixups_offset: u32,
+    pub exports_offset: u32,
+    pub imports_offset: u32,
+    pub end_offset: u32,
+}
+
+impl PackfileHeader {
+    /// Parse the header and section table of a packfile
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < HEADER_SIZE {
+            bail!(
+                "File is too small for a Havok packfile header ({} bytes)",
+                bytes.len()
+            );
+        }
+        if read_u32(bytes, 0) != MAGIC[0] || read_u32(bytes, 4) != MAGIC[1] {
+            bail!("Missing Havok packfile magic; not a binary HKX file");
+        }
+
+        let layout = &bytes[16..20];
+        let section_count = read_i32(bytes, 20);
+        if !(0..=16).contains(&section_count) {
+            bail!("Invalid section count {}", section_count);
+        }
+
+        let contents_version = bytes[40..56]
+            .iter()
+            .take_while(|&&b| b != 0 && b != 0xFF)
+            .map(|&b| b as char)
+            .collect();
+
+        // File version 11 and later store a variable-length predicate array after the header
+        let section_offset = read_i16(bytes, 62);
+        let sections_start = if section_offset > 0 {
+            HEADER_SIZE + section_offset as usize
+        } else {
+            HEADER_SIZE
+        };
+
+        let mut sections = Vec::with_capacity(section_count as usize);
+        for index in 0..section_count as usize {
+            let start = sections_start + index * SECTION_HEADER_SIZE;
+            if bytes.len() < start + SECTION_HEADER_SIZE {
+                bail!("File is truncated inside the section table");
+            }
+            let header = &bytes[start..start + SECTION_HEADER_SIZE];
+            sections.push(PackfileSection {
+                tag: header[..19]
+                    .iter()
+                    .take_while(|&&b| b != 0)
+                    .map(|&b| b as char)
+                    .collect(),
+                absolute_data_start: read_u32(header, 20),
+                local_fixups_offset: read_u32(header, 24),
+                global_fixups_offset: read_u32(header, 28),
+                virtual_fixups_offset: read_u32(header, 32),
+                exports_offset: read_u32(header, 36),
+                imports_offset: read_u32(header, 40),
+                end_offset: read_u32(header, 44),
+            });
+        }
+
+        Ok(Self {
+            user_tag: read_i32(bytes, 8),
+            file_version: read_i32(bytes, 12),
+            pointer_size: layout[0],
+            little_endian: layout[1] != 0,
+            reuse_padding_optimization: layout[2] != 0,
+            empty_base_class_optimization: layout[3] != 0,
+            contents_version,
+            sections,
+        })
+    }
+
+    /// Expected file size according to the section table
+    pub fn data_end(&self) -> u64 {
+        self.sections
+            .iter()
+            .map(|section| section.absolute_data_start as u64 + section.end_offset as u64)
+            .max()
+            .unwrap_or(HEADER_SIZE as u64)
+    }
+
+    pub fn section(&self, tag: &str) -> Option<&PackfileSection> {
+        self.sections.iter().find(|section| section.tag == tag)
+    }
+
+    /// Number of objects of each class, read from the `__data__` section's virtual fixups
+    pub fn object_classes(&self, bytes: &[u8]) -> Result<BTreeMap<String, usize>> {
+        let data = self
+            .section("__data__")
+            .context("Packfile has no __data__ section")?;
+        let start = data.absolute_data_start as usize + data.virtual_fixups_offset as usize;
+        let end = data.absolute_data_start as usize + data.exports_offset as usize;
+        if end > bytes.len() || start > end {
+            bail!("Virtual fixup table lies outside the file");
+        }
+
+        let mut classes = BTreeMap::new();
+        for fixup in bytes[start..end].chunks_exact(12) {
+            // Unused entries at the end of the table are filled with 0xFF
+            if read_u32(fixup, 0) == u32::MAX {
+                continue;
+            }
+            let section_index = read_u32(fixup, 4) as usize;
+            let name_offset = read_u32(fixup, 8) as usize;
+            let section = self
+                .sections
+                .get(section_index)
+                .context("Virtual fixup points at a missing section")?;
+
+            let name_start = section.absolute_data_start as usize + name_offset;
+            let name = bytes
+                .get(name_start..)
+                .context("Class name lies outside the file")?
+                .iter()
+                .take_while(|&&b| b != 0)
+                .map(|&b| b as char)
+                .collect::<String>();
+            *classes.entry(name).or_insert(0) += 1;
+        }
+
+        Ok(classes)
+    }
+
+    /// Signature of every class listed in the `__classnames__` section. The signature
+    /// changes with the class layout, so it identifies the class version.
+    pub fn class_signatures(&self, bytes: &[u8]) -> Result<BTreeMap<String, u32>> {
+        let names = self
+            .section("__classnames__")
+            .context("Packfile has no __classnames__ section")?;
+        let start = names.absolute_data_start as usize;
+        // Section data ends where its fixup tables begin
+        let end = start + names.local_fixups_offset as usize;
+        let data = bytes
+            .get(start..end)
+            .context("Class name section lies outside the file")?;
+
+        let mut signatures = BTreeMap::new();
+        let mut offset = 0;
+        // Each entry is a signature, a 0x09 separator and a null-terminated name; the
+        // section is padded with 0xFF
+        while offset + 5 <= data.len() && data[offset + 4] == 0x09 {
+            let signature = read_u32(data, offset);
+            let name = data[offset + 5..]
+                .iter()
+                .take_while(|&&b| b != 0)
+                .map(|&b| b as char)
+                .collect::<String>();
+            offset += 5 + name.len() + 1;
+            signatures.insert(name, signature);
+        }
+        Ok(signatures)
+    }
+
+    /// Short description such as `64-bit little endian (hk_2010.2.0-r1)`
+    pub fn describe(&self) -> String {
+        format!(
+            "{}-bit {} endian ({})",
+            self.pointer_size as u32 * 8,
+            if self.little_endian { "little" } else { "big" },
+            self.contents_version
+        )
+    }
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_i32(bytes: &[u8], offset: usize) -> i32 {
+    i32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_i16(bytes: &[u8], offset: usize) -> i16 {
+    i16::from_le_bytes(bytes[offset..offset + 2].try_into().unwrap())
+}
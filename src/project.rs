@@ -0,0 +1,193 @@
+//! Behavior projects: everything a `*Project.hkx` pulls in.
+//!
+//! The project's `hkbProjectStringData` lists its character files, each character's
+//! `hkbCharacterStringData` names the rig, the root behavior and the animations, and
+//! behaviors can pull in further behaviors through `hkbBehaviorReferenceGenerator`.
+//! All of these paths are relative to the project file's folder.
+
+use crate::hkx_document::HkxDocument;
+use crate::ConverterTool;
+use anyhow::{Context as AnyhowContext, Result};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectFileKind {
+    Project,
+    Character,
+    Skeleton,
+    Behavior,
+    Animation,
+}
+
+impl ProjectFileKind {
+    /// Tool for this kind of file when `batch_tool` is picked. HavokBehaviorPostProcess only
+    /// handles behavior graphs, so rigs and animations go through HCT (or hkxc without it).
+    pub fn tool(&self, batch_tool: ConverterTool, hct_available: bool) -> ConverterTool {
+        match (self, batch_tool) {
+            (
+                ProjectFileKind::Skeleton | ProjectFileKind::Animation,
+                ConverterTool::HavokBehaviorPostProcess,
+            ) => {
+                if hct_available {
+                    ConverterTool::Hct
+                } else {
+                    ConverterTool::HkxC
+                }
+            }
+            _ => batch_tool,
+        }
+    }
+}
+
+/// A file referenced by the project that doesn't exist on disk
+#[derive(Debug, Clone)]
+pub struct MissingFile {
+    pub path: PathBuf,
+    pub referenced_by: PathBuf,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProjectScan {
+    pub project: PathBuf,
+    /// Every file involved, in the order found, starting with the project itself
+    pub files: Vec<(PathBuf, ProjectFileKind)>,
+    /// The first character's rig, used for the project's animations
+    pub skeleton: Option<PathBuf>,
+    pub missing: Vec<MissingFile>,
+}
+
+impl ProjectScan {
+    pub async fn load(project: &Path) -> Result<Self> {
+        let root = project.parent().unwrap_or(Path::new("")).to_path_buf();
+        let mut scan = Self {
+            project: project.to_path_buf(),
+            files: vec![(project.to_path_buf(), ProjectFileKind::Project)],
+            skeleton: None,
+            missing: Vec::new(),
+        };
+
+        let document = HkxDocument::load(project).await?;
+        let strings = document
+            .objects_of_class("hkbProjectStringData")
+            .next()
+            .with_context(|| format!("{:?} has no hkbProjectStringData", project))?;
+
+        let mut characters = Vec::new();
+        for name in strings.strings("characterFilenames") {
+            if let Some(path) = scan.reference(&root, name, project, ProjectFileKind::Character) {
+                characters.push(path);
+            }
+        }
+        let mut behaviors = Vec::new();
+        for name in strings.strings("behaviorFilenames") {
+            behaviors.extend(scan.reference(&root, name, project, ProjectFileKind::Behavior));
+        }
+
+        for character in &characters {
+            let document = HkxDocument::load(character).await?;
+            let Some(strings) = document.objects_of_class("hkbCharacterStringData").next() else {
+                continue;
+            };
+            for param in ["rigName", "ragdollName"] {
+                for name in strings.strings(param) {
+                    let skeleton =
+                        scan.reference(&root, name, character, ProjectFileKind::Skeleton);
+                    if scan.skeleton.is_none() {
+                        scan.skeleton = skeleton;
+                    }
+                }
+            }
+            for name in strings.strings("behaviorFilename") {
+                behaviors.extend(scan.reference(&root, name, character, ProjectFileKind::Behavior));
+            }
+            for name in strings.strings("animationNames") {
+                scan.reference(&root, name, character, ProjectFileKind::Animation);
+            }
+        }
+
+        // Behaviors can reference each other, so keep going until no new ones turn up
+        while let Some(behavior) = behaviors.pop() {
+            let document = HkxDocument::load(&behavior).await?;
+            for generator in document.objects_of_class("hkbBehaviorReferenceGenerator") {
+                for name in generator.strings("behaviorName") {
+                    behaviors.extend(scan.reference(
+                        &root,
+                        name,
+                        &behavior,
+                        ProjectFileKind::Behavior,
+                    ));
+                }
+            }
+        }
+
+        Ok(scan)
+    }
+
+    /// Record a referenced file. Returns its path the first time an existing file is seen.
+    fn reference(
+        &mut self,
+        root: &Path,
+        name: &str,
+        referenced_by: &Path,
+        kind: ProjectFileKind,
+    ) -> Option<PathBuf> {
+        let name = name.trim();
+        if name.is_empty() {
+            return None;
+        }
+        let Some(path) = resolve(root, name) else {
+            let path = root.join(name.replace('\\', "/"));
+            if !self.missing.iter().any(|missing| missing.path == path) {
+                self.missing.push(MissingFile {
+                    path,
+                    referenced_by: referenced_by.to_path_buf(),
+                });
+            }
+            return None;
+        };
+        if self.files.iter().any(|(existing, _)| *existing == path) {
+            return None;
+        }
+        self.files.push((path.clone(), kind));
+        Some(path)
+    }
+
+    /// Folder of `file` relative to the project folder, so outputs keep the project's layout
+    pub fn relative_folder(&self, file: &Path) -> Option<PathBuf> {
+        let root = self.project.parent()?;
+        Some(file.parent()?.strip_prefix(root).ok()?.to_path_buf())
+    }
+
+    pub fn kind_of(&self, file: &Path) -> Option<ProjectFileKind> {
+        self.files
+            .iter()
+            .find(|(path, _)| path == file)
+            .map(|(_, kind)| *kind)
+    }
+}
+
+/// Resolve a Windows-style relative path below `root`, matching each component
+/// case-insensitively as the game does
+fn resolve(root: &Path, name: &str) -> Option<PathBuf> {
+    let mut path = root.to_path_buf();
+    for component in Path::new(&name.replace('\\', "/")).components() {
+        match component {
+            Component::Normal(part) => {
+                let exact = path.join(part);
+                path = if exact.exists() {
+                    exact
+                } else {
+                    fs::read_dir(&path)
+                        .ok()?
+                        .flatten()
+                        .find(|entry| entry.file_name().eq_ignore_ascii_case(part))?
+                        .path()
+                };
+            }
+            Component::ParentDir => path = path.parent()?.to_path_buf(),
+            _ => {}
+        }
+    }
+    path.is_file().then_some(path)
+}
@@ -0,0 +1,259 @@
+//! Animation Report tab: a sortable table of every animation in a folder, exportable as CSV.
+
+use crate::animation_report::{self, AnimationInfo, ReportColumn};
+use anyhow::Result;
+use eframe::egui;
+use egui::{Color32, RichText, Ui};
+use egui_extras::{Column, TableBuilder};
+use rfd::FileDialog;
+use std::fs;
+use std::path::PathBuf;
+use tokio::sync::mpsc;
+
+/// What the background scan reports
+enum ScanUpdate {
+    /// How many animation files the folder holds, once it has been listed
+    Listed(usize),
+    Read(PathBuf, Result<Vec<AnimationInfo>>),
+}
+
+pub struct ReportTab {
+    folder: Option<PathBuf>,
+    rows: Vec<AnimationInfo>,
+    /// Files that couldn't be read, with the reason
+    errors: Vec<(PathBuf, String)>,
+    /// Files read so far that hold no animation
+    skipped: usize,
+    scanned: usize,
+    /// `None` while the folder is still being listed
+    total: Option<usize>,
+    sort_column: ReportColumn,
+    sort_ascending: bool,
+    status: Option<std::result::Result<String, String>>,
+    scan_rx: Option<mpsc::UnboundedReceiver<ScanUpdate>>,
+    tokio_handle: tokio::runtime::Handle,
+}
+
+impl ReportTab {
+    pub fn new(tokio_handle: tokio::runtime::Handle) -> Self {
+        Self {
+            folder: None,
+            rows: Vec::new(),
+            errors: Vec::new(),
+            skipped: 0,
+            scanned: 0,
+            total: None,
+            sort_column: ReportColumn::File,
+            sort_ascending: true,
+            status: None,
+            scan_rx: None,
+            tokio_handle,
+        }
+    }
+
+    /// Read every animation file in `folder` in the background
+    pub fn scan(&mut self, folder: PathBuf) {
+        self.rows.clear();
+        self.errors.clear();
+        self.skipped = 0;
+        self.scanned = 0;
+        self.total = None;
+        self.status = None;
+        self.folder = Some(folder.clone());
+
+        let (scan_tx, scan_rx) = mpsc::unbounded_channel();
+        self.scan_rx = Some(scan_rx);
+        self.tokio_handle.spawn(async move {
+            // Listing a large folder takes a while, so it stays off the UI thread too
+            let files =
+                tokio::task::spawn_blocking(move || animation_report::animation_files(&folder))
+                    .await
+                    .unwrap_or_default();
+            if scan_tx.send(ScanUpdate::Listed(files.len())).is_err() {
+                return;
+            }
+            for file in files {
+                let result = animation_report::read_animations(&file).await;
+                if scan_tx.send(ScanUpdate::Read(file, result)).is_err() {
+                    // A newer scan replaced this one
+                    return;
+                }
+            }
+        });
+    }
+
+    fn poll_scan(&mut self, ui: &Ui) {
+        let Some(scan_rx) = &mut self.scan_rx else {
+            return;
+        };
+
+        let mut received = false;
+        let mut finished = false;
+        loop {
+            match scan_rx.try_recv() {
+                Ok(ScanUpdate::Listed(total)) => self.total = Some(total),
+                Ok(ScanUpdate::Read(file, result)) => {
+                    received = true;
+                    self.scanned += 1;
+                    match result {
+                        Ok(animations) if animations.is_empty() => self.skipped += 1,
+                        Ok(animations) => self.rows.extend(animations),
+                        Err(e) => self.errors.push((file, format!("{:#}", e))),
+                    }
+                }
+                Err(mpsc::error::TryRecvError::Empty) => break,
+                Err(mpsc::error::TryRecvError::Disconnected) => {
+                    finished = true;
+                    break;
+                }
+            }
+        }
+        if received {
+            self.sort();
+        }
+        if finished {
+            self.scan_rx = None;
+        } else {
+            ui.ctx().request_repaint();
+        }
+    }
+
+    fn sort(&mut self) {
+        let column = self.sort_column;
+        self.rows.sort_by(|a, b| column.compare(a, b));
+        if !self.sort_ascending {
+            self.rows.reverse();
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui) {
+        self.poll_scan(ui);
+
+        ui.horizontal(|ui| {
+            if ui.button("Choose Folder").clicked() {
+                if let Some(folder) = FileDialog::new().pick_folder() {
+                    self.scan(folder);
+                }
+            }
+            if let Some(folder) = &self.folder {
+                ui.label(folder.to_string_lossy());
+            }
+            if ui
+                .add_enabled(
+                    !self.rows.is_empty() && self.scan_rx.is_none(),
+                    egui::Button::new("Export CSV"),
+                )
+                .clicked()
+            {
+                self.export_csv();
+            }
+        });
+
+        if self.scan_rx.is_some() {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                match self.total {
+                    Some(total) => ui.label(format!("Reading {}/{} files...", self.scanned, total)),
+                    None => ui.label("Listing files..."),
+                };
+            });
+        } else if self.folder.is_some() {
+            ui.label(format!(
+                "{} animations in {} files; {} files hold no animation",
+                self.rows.len(),
+                self.scanned,
+                self.skipped
+            ));
+        }
+        match &self.status {
+            Some(Ok(message)) => {
+                ui.label(RichText::new(message).color(Color32::from_rgb(100, 200, 100)));
+            }
+            Some(Err(error)) => {
+                ui.label(RichText::new(error).color(Color32::from_rgb(255, 120, 120)));
+            }
+            None => {}
+        }
+        if !self.errors.is_empty() {
+            egui::CollapsingHeader::new(
+                RichText::new(format!("{} files could not be read", self.errors.len()))
+                    .color(Color32::from_rgb(255, 120, 120)),
+            )
+            .id_salt("report_errors")
+            .show(ui, |ui| {
+                for (file, error) in &self.errors {
+                    ui.label(format!("{}: {}", file.to_string_lossy(), error));
+                }
+            });
+        }
+        ui.separator();
+
+        self.render_table(ui);
+    }
+
+    fn render_table(&mut self, ui: &mut Ui) {
+        let folder = self.folder.clone().unwrap_or_default();
+        let mut clicked_column = None;
+        let row_height = ui.text_style_height(&egui::TextStyle::Body);
+
+        TableBuilder::new(ui)
+            .id_salt("animation_report")
+            .striped(true)
+            .resizable(true)
+            .column(Column::initial(300.0).at_least(100.0).clip(true))
+            .columns(Column::auto().at_least(60.0), ReportColumn::ALL.len() - 1)
+            .header(row_height + 6.0, |mut header| {
+                for column in ReportColumn::ALL {
+                    header.col(|ui| {
+                        let arrow = match (column == self.sort_column, self.sort_ascending) {
+                            (true, true) => " ⏶",
+                            (true, false) => " ⏷",
+                            (false, _) => "",
+                        };
+                        if ui
+                            .button(RichText::new(format!("{}{}", column.label(), arrow)).strong())
+                            .clicked()
+                        {
+                            clicked_column = Some(column);
+                        }
+                    });
+                }
+            })
+            .body(|body| {
+                body.rows(row_height, self.rows.len(), |mut row| {
+                    let info = &self.rows[row.index()];
+                    for column in ReportColumn::ALL {
+                        row.col(|ui| {
+                            ui.label(column.value(info, &folder));
+                        });
+                    }
+                });
+            });
+
+        if let Some(column) = clicked_column {
+            if column == self.sort_column {
+                self.sort_ascending = !self.sort_ascending;
+            } else {
+                self.sort_column = column;
+                self.sort_ascending = true;
+            }
+            self.sort();
+        }
+    }
+
+    fn export_csv(&mut self) {
+        let Some(file) = FileDialog::new()
+            .add_filter("CSV files", &["csv"])
+            .set_file_name("animation_report.csv")
+            .save_file()
+        else {
+            return;
+        };
+        let folder = self.folder.clone().unwrap_or_default();
+        self.status = Some(
+            fs::write(&file, animation_report::to_csv(&self.rows, &folder))
+                .map(|()| format!("Exported {}", file.to_string_lossy()))
+                .map_err(|e| format!("Failed to write {:?}: {}", file, e)),
+        );
+    }
+}
@@ -0,0 +1,146 @@
+//! Settings persisted between runs, stored as `key=value` lines in the user's config folder.
+
+use crate::tool_options::ToolOptions;
+use anyhow::{Context as AnyhowContext, Result};
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Settings {
+    /// Explicit `hctStandAloneFilterManager.exe`, overriding auto-detection
+    pub hct_path: Option<PathBuf>,
+    /// `ModOrganizer.ini` of the MO2 instance picked last
+    pub mo2_ini: Option<PathBuf>,
+    /// Dropped folders add their subfolders' files too
+    pub recursive_drop: bool,
+    /// `;`-separated glob patterns applied to every added input
+    pub include_globs: String,
+    pub exclude_globs: String,
+    pub tool_options: ToolOptions,
+}
+
+impl Settings {
+    /// Load saved settings, falling back to defaults if there are none
+    pub fn load() -> Self {
+        let mut settings = Self::default();
+        let Some(text) = Self::file_path().and_then(|path| fs::read_to_string(path).ok()) else {
+            return settings;
+        };
+
+        for line in text.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = value.trim();
+            if value.is_empty() {
+                continue;
+            }
+            match key.trim() {
+                "hct_path" => settings.hct_path = Some(PathBuf::from(value)),
+                "mo2_ini" => settings.mo2_ini = Some(PathBuf::from(value)),
+                "recursive_drop" => settings.recursive_drop = value == "true",
+                "include_globs" => settings.include_globs = value.to_string(),
+                "exclude_globs" => settings.exclude_globs = value.to_string(),
+                key => {
+                    settings.tool_options.read_setting(key, value);
+                }
+            }
+        }
+        settings
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::file_path().context("Could not determine settings folder")?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("Failed to create settings folder")?;
+        }
+
+        let mut text = String::new();
+        if let Some(hct_path) = &self.hct_path {
+            text.push_str(&format!("hct_path={}\n", hct_path.display()));
+        }
+        if let Some(mo2_ini) = &self.mo2_ini {
+            text.push_str(&format!("mo2_ini={}\n", mo2_ini.display()));
+        }
+        if self.recursive_drop {
+            text.push_str("recursive_drop=true\n");
+        }
+        for (key, value) in [
+            ("include_globs", &self.include_globs),
+            ("exclude_globs", &self.exclude_globs),
+        ] {
+            if !value.trim().is_empty() {
+                text.push_str(&format!("{}={}\n", key, value.trim()));
+            }
+        }
+        self.tool_options.write_settings(&mut text);
+        fs::write(&path, text).with_context(|| format!("Failed to write {:?}", path))
+    }
+
+    fn file_path() -> Option<PathBuf> {
+        Some(Self::folder()?.join("settings.ini"))
+    }
+
+    /// The user's config folder for this program
+    pub fn folder() -> Option<PathBuf> {
+        let base = std::env::var_os("APPDATA")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+        Some(base.join("composite-hkx-conversion"))
+    }
+}
+
+// Named tool options, one `<name>.ini` file each in the `presets` folder next to the settings
+fn presets_folder() -> Result<PathBuf> {
+    Ok(Settings::folder()
+        .context("Could not determine settings folder")?
+        .join("presets"))
+}
+
+fn preset_path(name: &str) -> Result<PathBuf> {
+    let name = name.trim();
+    if name.is_empty() || name.contains(['/', '\\', ':', '*', '?', '"', '<', '>', '|']) {
+        anyhow::bail!("{:?} can't be used as a preset name", name);
+    }
+    Ok(presets_folder()?.join(format!("{}.ini", name)))
+}
+
+/// Names of the saved tool option presets, sorted
+pub fn preset_names() -> Vec<String> {
+    let Ok(entries) = presets_folder().and_then(|folder| Ok(fs::read_dir(folder)?)) else {
+        return Vec::new();
+    };
+    let mut names = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "ini"))
+        .filter_map(|path| Some(path.file_stem()?.to_string_lossy().to_string()))
+        .collect::<Vec<_>>();
+    names.sort_by_key(|name| name.to_lowercase());
+    names
+}
+
+pub fn load_preset(name: &str) -> Result<ToolOptions> {
+    let path = preset_path(name)?;
+    let text = fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+    let mut options = ToolOptions::default();
+    for (key, value) in text.lines().filter_map(|line| line.split_once('=')) {
+        options.read_setting(key.trim(), value.trim());
+    }
+    Ok(options)
+}
+
+pub fn save_preset(name: &str, options: &ToolOptions) -> Result<()> {
+    let path = preset_path(name)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("Failed to create presets folder")?;
+    }
+    let mut text = String::new();
+    options.write_settings(&mut text);
+    fs::write(&path, text).with_context(|| format!("Failed to write {:?}", path))
+}
+
+pub fn delete_preset(name: &str) -> Result<()> {
+    let path = preset_path(name)?;
+    fs::remove_file(&path).with_context(|| format!("Failed to delete {:?}", path))
+}
@@ -0,0 +1,277 @@
+//! Choosing the skeleton for each animation in a KF batch.
+//!
+//! Skyrim keeps one skeleton per creature at `meshes/actors/<creature>/character assets/skeleton.hkx`,
+//! so animations under `actors/` find theirs by walking up their folders. Folder overrides
+//! take precedence, and the default skeleton covers everything else. Skeletons are read
+//! through serde_hkx to make sure they really are skeletons before hkxcmd gets them.
+
+use crate::hkx_document::HkxDocument;
+use anyhow::{bail, Context as AnyhowContext, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tokio::sync::oneshot;
+
+const CHARACTER_ASSETS: &str = "character assets";
+const SKELETON_FILE: &str = "skeleton.hkx";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkeletonSource {
+    /// A folder override set in the GUI
+    Override,
+    /// The nearest `character assets/skeleton.hkx`
+    Detected,
+    /// The default skeleton picked for the batch
+    Default,
+}
+
+impl SkeletonSource {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SkeletonSource::Override => "override",
+            SkeletonSource::Detected => "detected",
+            SkeletonSource::Default => "default",
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SkeletonMap {
+    /// Skeleton used for every file inside a folder, most specific folder winning
+    pub overrides: Vec<(PathBuf, PathBuf)>,
+    /// Detected skeleton per animation folder, so the file list doesn't hit the disk every frame
+    detected: HashMap<PathBuf, Option<PathBuf>>,
+}
+
+impl SkeletonMap {
+    /// The skeleton to use for `file`, and where it came from
+    pub fn skeleton_for(
+        &mut self,
+        file: &Path,
+        default: Option<&Path>,
+    ) -> Option<(PathBuf, SkeletonSource)> {
+        let override_skeleton = self
+            .overrides
+            .iter()
+            .filter(|(folder, _)| file.starts_with(folder))
+            .max_by_key(|(folder, _)| folder.components().count())
+            .map(|(_, skeleton)| (skeleton.clone(), SkeletonSource::Override));
+        if override_skeleton.is_some() {
+            return override_skeleton;
+        }
+
+        let folder = file.parent()?.to_path_buf();
+        let detected = self
+            .detected
+            .entry(folder)
+            .or_insert_with_key(|folder| find_skeleton(folder))
+            .clone();
+        detected
+            .map(|skeleton| (skeleton, SkeletonSource::Detected))
+            .or_else(|| default.map(|skeleton| (skeleton.to_path_buf(), SkeletonSource::Default)))
+    }
+
+    pub fn set_override(&mut self, folder: PathBuf, skeleton: PathBuf) {
+        self.overrides.retain(|(existing, _)| *existing != folder);
+        self.overrides.push((folder, skeleton));
+    }
+
+    /// Forget detected skeletons, e.g. before a conversion in case files were added on disk
+    pub fn rescan(&mut self) {
+        self.detected.clear();
+    }
+}
+
+/// Find the nearest `character assets/skeleton.hkx` for an animation folder under
+/// `meshes/actors/<creature>/`, searching no higher than the creature folder
+pub fn find_skeleton(folder: &Path) -> Option<PathBuf> {
+    let is_under_actors = folder.ancestors().any(|dir| {
+        dir.parent()
+            .and_then(Path::file_name)
+            .is_some_and(|name| name.eq_ignore_ascii_case("actors"))
+    });
+    if !is_under_actors {
+        return None;
+    }
+
+    for dir in folder.ancestors() {
+        let parent_is_actors = dir
+            .parent()
+            .and_then(Path::file_name)
+            .is_some_and(|name| name.eq_ignore_ascii_case("actors"));
+
+        if let Some(skeleton) = child_ignore_case(dir, CHARACTER_ASSETS)
+            .and_then(|assets| child_ignore_case(&assets, SKELETON_FILE))
+        {
+            return Some(skeleton);
+        }
+        // `dir` is the creature folder; anything above it belongs to other creatures
+        if parent_is_actors {
+            break;
+        }
+    }
+    None
+}
+
+/// Look up a directory entry case-insensitively, as the game's paths are
+fn child_ignore_case(dir: &Path, name: &str) -> Option<PathBuf> {
+    let exact = dir.join(name);
+    if exact.exists() {
+        return Some(exact);
+    }
+    fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .find(|entry| entry.file_name().eq_ignore_ascii_case(name))
+        .map(|entry| entry.path())
+}
+
+/// Short form of a skeleton path for the file list, e.g. `dragon/character assets/skeleton.hkx`
+pub fn short_path(skeleton: &Path) -> String {
+    let components = skeleton
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy().to_string())
+        .collect::<Vec<_>>();
+    components[components.len().saturating_sub(3)..].join("/")
+}
+
+/// The `hkaSkeleton` a KF conversion binds its tracks to
+#[derive(Debug, Clone)]
+pub struct SkeletonInfo {
+    pub name: String,
+    pub bone_count: usize,
+}
+
+impl SkeletonInfo {
+    /// Read a skeleton file, failing if it holds no `hkaSkeleton` (e.g. a behavior or animation)
+    pub async fn load(path: &Path) -> Result<Self> {
+        let document = HkxDocument::load(path).await?;
+        Self::from_document(&document).with_context(|| format!("{:?} is not a skeleton", path))
+    }
+
+    pub fn from_document(document: &HkxDocument) -> Result<Self> {
+        // Creature skeletons also hold a ragdoll skeleton; the animation one comes first
+        let skeleton = document
+            .objects_of_class("hkaSkeleton")
+            .next()
+            .context("File contains no hkaSkeleton")?;
+        let bone_count = skeleton.array_len("bones").unwrap_or_default();
+        if bone_count == 0 {
+            bail!("hkaSkeleton has no bones");
+        }
+
+        Ok(Self {
+            name: skeleton.text("name").unwrap_or_default().to_string(),
+            bone_count,
+        })
+    }
+
+    /// Ways an animation doesn't fit this skeleton, as warnings for the file list
+    pub fn check_animation(&self, animation: &HkxDocument) -> Vec<String> {
+        let mut warnings = Vec::new();
+        let track_count = animation
+            .objects
+            .iter()
+            .find_map(|object| object.text("numberOfTransformTracks"))
+            .and_then(|count| count.parse::<usize>().ok());
+        if let Some(track_count) = track_count {
+            if track_count > self.bone_count {
+                warnings.push(format!(
+                    "Animation has {} transform tracks but skeleton {:?} has only {} bones",
+                    track_count, self.name, self.bone_count
+                ));
+            }
+        }
+
+        let Some(binding) = animation.objects_of_class("hkaAnimationBinding").next() else {
+            warnings.push("Animation has no hkaAnimationBinding".to_string());
+            return warnings;
+        };
+
+        let skeleton_name = binding.text("originalSkeletonName").unwrap_or_default();
+        if !skeleton_name.is_empty() && skeleton_name != self.name {
+            warnings.push(format!(
+                "Animation is bound to skeleton {:?}, not {:?}",
+                skeleton_name, self.name
+            ));
+        }
+
+        let bone_indices = binding
+            .text("transformTrackToBoneIndices")
+            .unwrap_or_default()
+            .split_whitespace()
+            .filter_map(|index| index.parse::<usize>().ok())
+            .collect::<Vec<_>>();
+        if let Some(track_count) = track_count {
+            if !bone_indices.is_empty() && bone_indices.len() != track_count {
+                warnings.push(format!(
+                    "Binding maps {} tracks but the animation has {}",
+                    bone_indices.len(),
+                    track_count
+                ));
+            }
+        }
+        if let Some(&max_index) = bone_indices.iter().max() {
+            if max_index >= self.bone_count {
+                warnings.push(format!(
+                    "Binding refers to bone {} but skeleton {:?} has only {} bones",
+                    max_index, self.name, self.bone_count
+                ));
+            }
+        }
+
+        warnings
+    }
+
+    pub fn describe(&self) -> String {
+        format!("{} ({} bones)", self.name, self.bone_count)
+    }
+}
+
+enum SkeletonCheck {
+    Loading(oneshot::Receiver<Result<SkeletonInfo>>),
+    Done(std::result::Result<SkeletonInfo, String>),
+}
+
+/// Reads picked skeletons in the background so the GUI can show what they contain
+pub struct SkeletonValidator {
+    checks: HashMap<PathBuf, SkeletonCheck>,
+    tokio_handle: tokio::runtime::Handle,
+}
+
+impl SkeletonValidator {
+    pub fn new(tokio_handle: tokio::runtime::Handle) -> Self {
+        Self {
+            checks: HashMap::new(),
+            tokio_handle,
+        }
+    }
+
+    /// What `path` contains, or `None` while it is still being read
+    pub fn status(&mut self, path: &Path) -> Option<&std::result::Result<SkeletonInfo, String>> {
+        let check = self.checks.entry(path.to_path_buf()).or_insert_with(|| {
+            let (tx, rx) = oneshot::channel();
+            let path = path.to_path_buf();
+            self.tokio_handle.spawn(async move {
+                let _ = tx.send(SkeletonInfo::load(&path).await);
+            });
+            SkeletonCheck::Loading(rx)
+        });
+
+        if let SkeletonCheck::Loading(rx) = check {
+            match rx.try_recv() {
+                Ok(result) => *check = SkeletonCheck::Done(result.map_err(|e| format!("{:#}", e))),
+                Err(oneshot::error::TryRecvError::Empty) => return None,
+                Err(oneshot::error::TryRecvError::Closed) => {
+                    *check =
+                        SkeletonCheck::Done(Err("Reading the skeleton was interrupted".to_string()))
+                }
+            }
+        }
+
+        match check {
+            SkeletonCheck::Done(result) => Some(result),
+            SkeletonCheck::Loading(_) => None,
+        }
+    }
+}
@@ -0,0 +1,392 @@
+//! Extra command line options for the bundled converters, on top of the arguments each
+//! conversion mode always passes.
+
+use crate::{ConversionMode, ConverterTool, OutputFormat};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ToolOptions {
+    pub hkxcmd: HkxCmdOptions,
+    pub hkxconv: HkxConvOptions,
+    pub post_process: PostProcessOptions,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HkxCmdOptions {
+    /// `-d:<level>`, left to hkxcmd's default (INFO) when unset
+    pub debug_level: Option<DebugLevel>,
+    /// `-f <flags>` for `convert` and `ConvertKF`; ExportKF writes KF, which has no Havok flags
+    pub save_flags: HkxCmdSaveFlags,
+    /// `-v:<platform>` for `convert` and `ConvertKF`; follows the output format when unset
+    pub platform: Option<HavokPlatform>,
+    pub export_kf: ExportKfOptions,
+    pub extra_args: String,
+}
+
+/// Options of hkxcmd's ExportKF; each is left to hkxcmd's default when empty
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExportKfOptions {
+    /// `-v:<version>`, the NIF version to write, e.g. `20.2.0.7`
+    pub nif_version: String,
+    /// `-u:<version>`, the NIF user version
+    pub user_version: Option<u32>,
+    /// `-u2:<version>`, the NIF user version 2
+    pub user_version2: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HkxConvOptions {
+    /// `-v <format>`; follows the output format when unset
+    pub format: Option<HkxConvFormat>,
+    pub extra_args: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PostProcessOptions {
+    /// `--platformAmd64`: write a 64-bit (SE) packfile instead of a 32-bit one
+    pub platform_amd64: bool,
+    pub extra_args: String,
+}
+
+impl Default for PostProcessOptions {
+    fn default() -> Self {
+        Self {
+            platform_amd64: true,
+            extra_args: String::new(),
+        }
+    }
+}
+
+/// Havok platform hkxcmd writes with `-v:`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HavokPlatform {
+    Xml,
+    Win32,
+    Amd64,
+}
+
+impl HavokPlatform {
+    pub const ALL: [Self; 3] = [Self::Xml, Self::Win32, Self::Amd64];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Xml => "XML",
+            Self::Win32 => "WIN32",
+            Self::Amd64 => "AMD64",
+        }
+    }
+
+    pub fn parse(text: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|platform| platform.as_str().eq_ignore_ascii_case(text))
+    }
+
+    /// The platform that writes `format`; KF has none, so it gets hkxcmd's 64-bit default
+    pub fn for_format(format: OutputFormat) -> Self {
+        match format {
+            OutputFormat::Xml => Self::Xml,
+            OutputFormat::SkyrimLE => Self::Win32,
+            OutputFormat::SkyrimSE | OutputFormat::Kf => Self::Amd64,
+        }
+    }
+
+    pub fn output_format(&self) -> OutputFormat {
+        match self {
+            Self::Xml => OutputFormat::Xml,
+            Self::Win32 => OutputFormat::SkyrimLE,
+            Self::Amd64 => OutputFormat::SkyrimSE,
+        }
+    }
+}
+
+/// Format hkxconv writes with `-v`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HkxConvFormat {
+    Xml,
+    Hkx,
+}
+
+impl HkxConvFormat {
+    pub const ALL: [Self; 2] = [Self::Xml, Self::Hkx];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Xml => "xml",
+            Self::Hkx => "hkx",
+        }
+    }
+
+    pub fn parse(text: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|format| format.as_str().eq_ignore_ascii_case(text))
+    }
+
+    pub fn for_format(format: OutputFormat) -> Self {
+        match format {
+            OutputFormat::Xml => Self::Xml,
+            _ => Self::Hkx,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebugLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Verbose,
+}
+
+impl DebugLevel {
+    pub const ALL: [Self; 5] = [
+        Self::Error,
+        Self::Warn,
+        Self::Info,
+        Self::Debug,
+        Self::Verbose,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Error => "ERROR",
+            Self::Warn => "WARN",
+            Self::Info => "INFO",
+            Self::Debug => "DEBUG",
+            Self::Verbose => "VERBOSE",
+        }
+    }
+
+    pub fn parse(text: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|level| level.as_str().eq_ignore_ascii_case(text))
+    }
+}
+
+/// Havok `hkSerializeUtil` save flags understood by hkxcmd
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HkxCmdSaveFlags {
+    pub serialize_ignored_members: bool,
+    pub write_attributes: bool,
+    pub concise: bool,
+}
+
+impl HkxCmdSaveFlags {
+    pub fn flags_mut(&mut self) -> [(&'static str, &mut bool); 3] {
+        [
+            (
+                "SAVE_SERIALIZE_IGNORED_MEMBERS",
+                &mut self.serialize_ignored_members,
+            ),
+            ("SAVE_WRITE_ATTRIBUTES", &mut self.write_attributes),
+            ("SAVE_CONCISE", &mut self.concise),
+        ]
+    }
+
+    /// Flags joined the way hkxcmd expects (`A|B`), or `None` to keep its default
+    pub fn to_arg(&self) -> Option<String> {
+        let flags = [
+            (
+                "SAVE_SERIALIZE_IGNORED_MEMBERS",
+                self.serialize_ignored_members,
+            ),
+            ("SAVE_WRITE_ATTRIBUTES", self.write_attributes),
+            ("SAVE_CONCISE", self.concise),
+        ]
+        .into_iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(name, _)| name)
+        .collect::<Vec<_>>();
+        (!flags.is_empty()).then(|| flags.join("|"))
+    }
+
+    pub fn parse(text: &str) -> Self {
+        let mut flags = Self::default();
+        for name in text.split('|').map(str::trim) {
+            for (flag, enabled) in flags.flags_mut() {
+                if flag == name {
+                    *enabled = true;
+                }
+            }
+        }
+        flags
+    }
+}
+
+impl ToolOptions {
+    /// Options to pass to `tool` before its file arguments
+    pub fn args(&self, tool: ConverterTool, mode: ConversionMode) -> Vec<String> {
+        let mut args = Vec::new();
+        let extra_args = match tool {
+            ConverterTool::HkxCmd => {
+                if let Some(level) = self.hkxcmd.debug_level {
+                    args.push(format!("-d:{}", level.as_str()));
+                }
+                if mode == ConversionMode::HkxToKf {
+                    let export_kf = &self.hkxcmd.export_kf;
+                    if !export_kf.nif_version.trim().is_empty() {
+                        args.push(format!("-v:{}", export_kf.nif_version.trim()));
+                    }
+                    if let Some(user_version) = export_kf.user_version {
+                        args.push(format!("-u:{}", user_version));
+                    }
+                    if let Some(user_version2) = export_kf.user_version2 {
+                        args.push(format!("-u2:{}", user_version2));
+                    }
+                } else if let Some(flags) = self.hkxcmd.save_flags.to_arg() {
+                    args.push("-f".to_string());
+                    args.push(flags);
+                }
+                &self.hkxcmd.extra_args
+            }
+            ConverterTool::HkxConv => &self.hkxconv.extra_args,
+            ConverterTool::HavokBehaviorPostProcess => {
+                if self.post_process.platform_amd64 {
+                    args.push("--platformAmd64".to_string());
+                }
+                &self.post_process.extra_args
+            }
+            // hkxc runs in-process and HCT is configured through its .hko file
+            ConverterTool::HkxC | ConverterTool::Hct => return args,
+        };
+        args.extend(split_args(extra_args));
+        args
+    }
+
+    /// Platform hkxcmd writes for `convert` and `ConvertKF`
+    pub fn hkxcmd_platform(&self, output_format: OutputFormat) -> HavokPlatform {
+        self.hkxcmd
+            .platform
+            .unwrap_or_else(|| HavokPlatform::for_format(output_format))
+    }
+
+    pub fn hkxconv_format(&self, output_format: OutputFormat) -> HkxConvFormat {
+        self.hkxconv
+            .format
+            .unwrap_or_else(|| HkxConvFormat::for_format(output_format))
+    }
+
+    /// Format `tool` writes with these options when `output_format` is selected
+    pub fn produced_format(
+        &self,
+        tool: ConverterTool,
+        mode: ConversionMode,
+        output_format: OutputFormat,
+    ) -> OutputFormat {
+        match (mode, tool) {
+            (ConversionMode::HkxToKf, _) => OutputFormat::Kf,
+            (_, ConverterTool::HkxCmd) => self.hkxcmd_platform(output_format).output_format(),
+            (_, ConverterTool::HkxConv) => match self.hkxconv_format(output_format) {
+                HkxConvFormat::Xml => OutputFormat::Xml,
+                HkxConvFormat::Hkx if output_format == OutputFormat::Xml => OutputFormat::SkyrimSE,
+                HkxConvFormat::Hkx => output_format,
+            },
+            (_, ConverterTool::HavokBehaviorPostProcess) if self.post_process.platform_amd64 => {
+                OutputFormat::SkyrimSE
+            }
+            (_, ConverterTool::HavokBehaviorPostProcess) => OutputFormat::SkyrimLE,
+            _ => output_format,
+        }
+    }
+
+    /// Read one `key=value` line written by [`Self::write_settings`]; false for other keys
+    pub fn read_setting(&mut self, key: &str, value: &str) -> bool {
+        match key {
+            "hkxcmd_debug_level" => self.hkxcmd.debug_level = DebugLevel::parse(value),
+            "hkxcmd_save_flags" => self.hkxcmd.save_flags = HkxCmdSaveFlags::parse(value),
+            "hkxcmd_platform" => self.hkxcmd.platform = HavokPlatform::parse(value),
+            "hkxcmd_kf_nif_version" => self.hkxcmd.export_kf.nif_version = value.to_string(),
+            "hkxcmd_kf_user_version" => self.hkxcmd.export_kf.user_version = value.parse().ok(),
+            "hkxcmd_kf_user_version2" => self.hkxcmd.export_kf.user_version2 = value.parse().ok(),
+            "hkxcmd_extra_args" => self.hkxcmd.extra_args = value.to_string(),
+            "hkxconv_format" => self.hkxconv.format = HkxConvFormat::parse(value),
+            "hkxconv_extra_args" => self.hkxconv.extra_args = value.to_string(),
+            "post_process_platform_amd64" => self.post_process.platform_amd64 = value == "true",
+            "post_process_extra_args" => self.post_process.extra_args = value.to_string(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// `key=value` lines for every option that differs from the default
+    pub fn write_settings(&self, text: &mut String) {
+        let mut lines = Vec::new();
+        if let Some(level) = self.hkxcmd.debug_level {
+            lines.push(("hkxcmd_debug_level", level.as_str().to_string()));
+        }
+        if let Some(flags) = self.hkxcmd.save_flags.to_arg() {
+            lines.push(("hkxcmd_save_flags", flags));
+        }
+        if let Some(platform) = self.hkxcmd.platform {
+            lines.push(("hkxcmd_platform", platform.as_str().to_string()));
+        }
+        let export_kf = &self.hkxcmd.export_kf;
+        lines.push((
+            "hkxcmd_kf_nif_version",
+            export_kf.nif_version.trim().to_string(),
+        ));
+        if let Some(user_version) = export_kf.user_version {
+            lines.push(("hkxcmd_kf_user_version", user_version.to_string()));
+        }
+        if let Some(user_version2) = export_kf.user_version2 {
+            lines.push(("hkxcmd_kf_user_version2", user_version2.to_string()));
+        }
+        lines.push((
+            "hkxcmd_extra_args",
+            self.hkxcmd.extra_args.trim().to_string(),
+        ));
+        if let Some(format) = self.hkxconv.format {
+            lines.push(("hkxconv_format", format.as_str().to_string()));
+        }
+        lines.push((
+            "hkxconv_extra_args",
+            self.hkxconv.extra_args.trim().to_string(),
+        ));
+        if !self.post_process.platform_amd64 {
+            lines.push(("post_process_platform_amd64", "false".to_string()));
+        }
+        lines.push((
+            "post_process_extra_args",
+            self.post_process.extra_args.trim().to_string(),
+        ));
+
+        for (key, value) in lines {
+            if !value.is_empty() {
+                text.push_str(&format!("{}={}\n", key, value));
+            }
+        }
+    }
+}
+
+/// Split a command line on whitespace, keeping double-quoted parts together
+pub fn split_args(text: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut has_arg = false;
+
+    for c in text.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                has_arg = true;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if has_arg {
+                    args.push(std::mem::take(&mut current));
+                    has_arg = false;
+                }
+            }
+            c => {
+                current.push(c);
+                has_arg = true;
+            }
+        }
+    }
+    if has_arg {
+        args.push(current);
+    }
+    args
+}
@@ -0,0 +1,105 @@
+//! What an external converter was run with and what it printed, kept so a failed file can be
+//! inspected instead of showing one line of mixed output.
+
+use anyhow::{Context as AnyhowContext, Result};
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+#[derive(Debug, Clone)]
+pub struct ToolRun {
+    /// The command as it could be pasted into a terminal
+    pub command_line: String,
+    pub working_dir: PathBuf,
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    /// Run as a library call inside this program rather than as a process
+    pub in_process: bool,
+}
+
+impl ToolRun {
+    pub fn new(command: &tokio::process::Command, output: &Output) -> Self {
+        let command = command.as_std();
+        let command_line = std::iter::once(command.get_program())
+            .chain(command.get_args())
+            .map(|arg| quote(&arg.to_string_lossy()))
+            .collect::<Vec<_>>()
+            .join(" ");
+        Self {
+            command_line,
+            working_dir: command
+                .get_current_dir()
+                .map(Path::to_path_buf)
+                .unwrap_or_else(|| std::env::current_dir().unwrap_or_default()),
+            exit_code: output.status.code(),
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+            in_process: false,
+        }
+    }
+
+    /// A conversion done by a library call, written as the equivalent command; its error, if
+    /// any, stands in for the output
+    pub fn in_process(program: &str, args: &[&str], error: Option<String>) -> Self {
+        let command_line = std::iter::once(program)
+            .chain(args.iter().copied())
+            .map(quote)
+            .collect::<Vec<_>>()
+            .join(" ");
+        Self {
+            command_line,
+            working_dir: std::env::current_dir().unwrap_or_default(),
+            exit_code: None,
+            stdout: String::new(),
+            stderr: error.unwrap_or_default(),
+            in_process: true,
+        }
+    }
+}
+
+fn quote(arg: &str) -> String {
+    if arg.is_empty() || arg.contains([' ', '\t', '"']) {
+        format!("\"{}\"", arg.replace('"', "\\\""))
+    } else {
+        arg.to_string()
+    }
+}
+
+/// A converter that exited with an error
+#[derive(Debug)]
+pub struct ToolFailure {
+    pub message: String,
+    pub run: ToolRun,
+}
+
+impl std::fmt::Display for ToolFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ToolFailure {}
+
+/// Show `path` selected in the system file manager
+pub fn open_in_explorer(path: &Path) -> Result<()> {
+    let mut command = if cfg!(windows) {
+        let mut command = std::process::Command::new("explorer");
+        // explorer wants `/select,<path>` as a single argument
+        let mut select = std::ffi::OsString::from("/select,");
+        select.push(path);
+        command.arg(select);
+        command
+    } else if cfg!(target_os = "macos") {
+        let mut command = std::process::Command::new("open");
+        command.arg("-R").arg(path);
+        command
+    } else {
+        let mut command = std::process::Command::new("xdg-open");
+        command.arg(path.parent().unwrap_or(path));
+        command
+    };
+    command
+        .spawn()
+        .with_context(|| format!("Failed to open {:?} in the file manager", path))?;
+    Ok(())
+}
@@ -0,0 +1,161 @@
+//! Output verification: a conversion only counts as successful once its output has been
+//! read back and matches the requested [`OutputFormat`].
+
+use crate::hkx_document::{self, HkxDocument};
+use crate::packfile::{self, PackfileHeader};
+use crate::OutputFormat;
+use anyhow::{bail, Context as AnyhowContext, Result};
+use std::fs;
+use std::path::Path;
+
+/// KF files written by hkxcmd are Gamebryo NIF streams
+const KF_MAGIC: &[u8] = b"Gamebryo File Format";
+
+pub async fn verify_output(path: &Path, format: OutputFormat) -> Result<()> {
+    let bytes = fs::read(path).context("Output file was not created")?;
+    if bytes.is_empty() {
+        bail!("Output file is empty");
+    }
+
+    match format {
+        OutputFormat::Kf => {
+            if !bytes.starts_with(KF_MAGIC) {
+                bail!("Output is not a KF file (missing Gamebryo header)");
+            }
+            // serde_hkx cannot read KF, so the header is all we can check
+            return Ok(());
+        }
+        OutputFormat::Xml => {
+            if !hkx_document::looks_like_xml(&bytes) {
+                match PackfileHeader::parse(&bytes) {
+                    Ok(header) => bail!(
+                        "Expected XML output but got a binary {} packfile",
+                        header.describe()
+                    ),
+                    Err(_) => bail!("Expected XML output but got binary data"),
+                }
+            }
+            check_xml_with_serde_hkx(&bytes).await?;
+        }
+        OutputFormat::SkyrimLE | OutputFormat::SkyrimSE => {
+            let header = PackfileHeader::parse(&bytes)?;
+            check_skyrim_header(&header, format)?;
+
+            let data_end = header.data_end();
+            if (bytes.len() as u64) < data_end {
+                bail!(
+                    "Output is truncated: {} bytes, but its sections end at byte {}",
+                    bytes.len(),
+                    data_end
+                );
+            }
+        }
+    }
+
+    // Parse the whole output, XML as is and binary packfiles through serde_hkx
+    let document = HkxDocument::load(path).await?;
+    if document.objects.is_empty() {
+        bail!("Output contains no objects");
+    }
+    if let Some(top_level_object) = &document.top_level_object {
+        if document.object(top_level_object).is_none() {
+            bail!("Top level object {} is missing", top_level_object);
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that a packfile header has the layout Skyrim LE (32-bit) or SE (64-bit) expects
+pub fn check_skyrim_header(header: &PackfileHeader, format: OutputFormat) -> Result<()> {
+    let expected_pointer_size = match format {
+        OutputFormat::SkyrimLE => 4,
+        OutputFormat::SkyrimSE => 8,
+        OutputFormat::Xml | OutputFormat::Kf => return Ok(()),
+    };
+
+    if header.pointer_size != expected_pointer_size || !header.little_endian {
+        bail!(
+            "Expected {} output ({}-byte pointers, little endian) but got a {} packfile",
+            format.label(),
+            expected_pointer_size,
+            header.describe()
+        );
+    }
+    if header.contents_version != packfile::SKYRIM_CONTENTS_VERSION {
+        bail!(
+            "Expected Havok version {} but output has {}",
+            packfile::SKYRIM_CONTENTS_VERSION,
+            header.contents_version
+        );
+    }
+
+    Ok(())
+}
+
+/// HavokBehaviorPostProcess rewrites a 32-bit packfile in place, as 64-bit with
+/// `--platformAmd64`. Check that the rewritten file really is a Skyrim packfile of `format`
+/// holding the same objects as the input.
+pub fn check_post_process_output(input: &Path, output: &Path, format: OutputFormat) -> Result<()> {
+    let input_bytes = fs::read(input).context("Failed to read input file")?;
+    let output_bytes = fs::read(output).context("Output file was not created")?;
+    if format == OutputFormat::SkyrimSE && input_bytes == output_bytes {
+        bail!("HavokBehaviorPostProcess left the file unchanged");
+    }
+
+    let input_header =
+        PackfileHeader::parse(&input_bytes).context("Failed to read input header")?;
+    let output_header =
+        PackfileHeader::parse(&output_bytes).context("HavokBehaviorPostProcess output")?;
+    check_skyrim_header(&output_header, format)
+        .context("HavokBehaviorPostProcess did not convert the file")?;
+
+    let input_classes = input_header
+        .object_classes(&input_bytes)
+        .context("Failed to read input classes")?;
+    let output_classes = output_header
+        .object_classes(&output_bytes)
+        .context("Failed to read output classes")?;
+    if input_classes != output_classes {
+        let mut differences = Vec::new();
+        for (class, &count) in &input_classes {
+            let converted = output_classes.get(class).copied().unwrap_or(0);
+            if converted != count {
+                differences.push(format!("{} ({} -> {})", class, count, converted));
+            }
+        }
+        for (class, &count) in &output_classes {
+            if !input_classes.contains_key(class) {
+                differences.push(format!("{} (0 -> {})", class, count));
+            }
+        }
+        bail!(
+            "HavokBehaviorPostProcess changed the objects in the file: {}",
+            differences.join(", ")
+        );
+    }
+
+    Ok(())
+}
+
+/// Have serde_hkx compile the XML to a throwaway packfile, which fails on anything it cannot read
+async fn check_xml_with_serde_hkx(bytes: &[u8]) -> Result<()> {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("hkx_verify_")
+        .tempdir()
+        .context("Failed to create temporary directory for verification")?;
+    // serde_hkx goes by the extension, which a custom output extension may have changed
+    let xml_path = temp_dir.path().join("verify.xml");
+    fs::write(&xml_path, bytes).context("Failed to copy the XML output")?;
+    let hkx_path = temp_dir.path().join("verify.hkx");
+
+    serde_hkx_features::convert::tokio::convert(
+        &xml_path,
+        Some(&hkx_path),
+        serde_hkx_features::OutFormat::Amd64,
+    )
+    .await
+    .context("serde_hkx could not read the XML output")?;
+
+    Ok(())
+}
@@ -0,0 +1,399 @@
+//! Minimal XML reader/writer for the documents this tool deals with.
+//!
+//! Havok packfile XML and HCT `.hko` option files only use elements, attributes, text,
+//! comments and the XML declaration, so a small hand-written parser is enough here and
+//! keeps us from pulling in a full XML stack.
+
+use anyhow::{bail, Result};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum XmlNode {
+    Element(XmlElement),
+    Text(String),
+    Comment(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct XmlElement {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<XmlNode>,
+}
+
+impl XmlElement {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_attribute(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.attributes.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn with_text(mut self, text: impl Into<String>) -> Self {
+        self.children.push(XmlNode::Text(text.into()));
+        self
+    }
+
+    pub fn with_child(mut self, child: XmlElement) -> Self {
+        self.children.push(XmlNode::Element(child));
+        self
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Iterate over the child elements, skipping text and comments
+    pub fn elements(&self) -> impl Iterator<Item = &XmlElement> {
+        self.children.iter().filter_map(|child| match child {
+            XmlNode::Element(element) => Some(element),
+            _ => None,
+        })
+    }
+
+    /// Concatenated text content of the direct children
+    pub fn text(&self) -> String {
+        self.children
+            .iter()
+            .filter_map(|child| match child {
+                XmlNode::Text(text) => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Serialize this element (and its children) with tab indentation
+    pub fn write(&self, out: &mut String, depth: usize) {
+        let indent = "\t".repeat(depth);
+        out.push_str(&indent);
+        out.push('<');
+        out.push_str(&self.name);
+        for (key, value) in &self.attributes {
+            out.push(' ');
+            out.push_str(key);
+            out.push_str("=\"");
+            out.push_str(&escape(value));
+            out.push('"');
+        }
+
+        if self.children.is_empty() {
+            out.push_str("></");
+            out.push_str(&self.name);
+            out.push_str(">\n");
+            return;
+        }
+        out.push('>');
+
+        // Elements with only text stay on one line, like Havok writes `hkparam`s
+        if self
+            .children
+            .iter()
+            .all(|child| matches!(child, XmlNode::Text(_)))
+        {
+            out.push_str(&escape(&self.text()));
+        } else {
+            out.push('\n');
+            for child in &self.children {
+                match child {
+                    XmlNode::Element(element) => element.write(out, depth + 1),
+                    XmlNode::Text(text) => {
+                        let text = text.trim();
+                        if !text.is_empty() {
+                            for line in text.lines() {
+                                out.push_str(&indent);
+                                out.push('\t');
+                                out.push_str(&escape(line.trim()));
+                                out.push('\n');
+                            }
+                        }
+                    }
+                    XmlNode::Comment(comment) => {
+                        out.push_str(&indent);
+                        out.push_str("\t<!--");
+                        out.push_str(comment);
+                        out.push_str("-->\n");
+                    }
+                }
+            }
+            out.push_str(&indent);
+        }
+        out.push_str("</");
+        out.push_str(&self.name);
+        out.push_str(">\n");
+    }
+
+    /// Serialize as a standalone document with an XML declaration
+    pub fn to_document_string(&self) -> String {
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        self.write(&mut out, 0);
+        out
+    }
+}
+
+/// Parse a document and return its root element
+pub fn parse(input: &str) -> Result<XmlElement> {
+    let mut parser = Parser {
+        input,
+        pos: input
+            .strip_prefix('\u{feff}')
+            .map_or(0, |_| '\u{feff}'.len_utf8()),
+    };
+    parser.skip_prolog()?;
+    let root = parser.parse_element()?;
+    Ok(root)
+}
+
+pub fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn unescape(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+        let entity = &rest[1..end];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn skip_past(&mut self, terminator: &str) -> Result<&'a str> {
+        let rest = &self.input[self.pos..];
+        let Some(end) = rest.find(terminator) else {
+            bail!("Unterminated XML construct, expected {:?}", terminator);
+        };
+        self.pos += end + terminator.len();
+        Ok(&rest[..end])
+    }
+
+    /// Skip the XML declaration, comments and doctype before the root element
+    fn skip_prolog(&mut self) -> Result<()> {
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if self.rest().starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if self.rest().starts_with("<!") {
+                self.skip_past(">")?;
+            } else if self.rest().starts_with('<') {
+                return Ok(());
+            } else {
+                bail!("Expected XML root element at byte {}", self.pos);
+            }
+        }
+    }
+
+    fn parse_name(&mut self) -> Result<String> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '>' | '/' | '='))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            bail!("Expected XML name at byte {}", self.pos);
+        }
+        self.pos += len;
+        Ok(rest[..len].to_string())
+    }
+
+    fn parse_element(&mut self) -> Result<XmlElement> {
+        if !self.rest().starts_with('<') {
+            bail!("Expected '<' at byte {}", self.pos);
+        }
+        self.pos += 1;
+        let mut element = XmlElement::new(self.parse_name()?);
+
+        // Attributes
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("/>") {
+                self.pos += 2;
+                return Ok(element);
+            }
+            if self.rest().starts_with('>') {
+                self.pos += 1;
+                break;
+            }
+            let name = self.parse_name()?;
+            self.skip_whitespace();
+            if !self.rest().starts_with('=') {
+                bail!("Expected '=' after attribute {:?}", name);
+            }
+            self.pos += 1;
+            self.skip_whitespace();
+            let quote = match self.rest().chars().next() {
+                Some(quote @ ('"' | '\'')) => quote,
+                _ => bail!("Expected quoted value for attribute {:?}", name),
+            };
+            self.pos += 1;
+            let value = self.skip_past(if quote == '"' { "\"" } else { "'" })?;
+            let value = unescape(value);
+            element.attributes.push((name, value));
+        }
+
+        // Content
+        loop {
+            let rest = self.rest();
+            if rest.is_empty() {
+                bail!("Unexpected end of XML inside <{}>", element.name);
+            }
+            if rest.starts_with("</") {
+                self.pos += 2;
+                let name = self.parse_name()?;
+                if name != element.name {
+                    bail!("Mismatched closing tag </{}> for <{}>", name, element.name);
+                }
+                self.skip_whitespace();
+                self.skip_past(">")?;
+                return Ok(element);
+            }
+            if rest.starts_with("<!--") {
+                self.pos += 4;
+                let comment = self.skip_past("-->")?.to_string();
+                element.children.push(XmlNode::Comment(comment));
+            } else if rest.starts_with("<![CDATA[") {
+                self.pos += 9;
+                let text = self.skip_past("]]>")?.to_string();
+                element.children.push(XmlNode::Text(text));
+            } else if rest.starts_with('<') {
+                let child = self.parse_element()?;
+                element.children.push(XmlNode::Element(child));
+            } else {
+                let len = rest.find('<').unwrap_or(rest.len());
+                let text = unescape(&rest[..len]);
+                self.pos += len;
+                if !text.trim().is_empty() {
+                    element.children.push(XmlNode::Text(text));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_entities() {
+        let root =
+            parse(r#"<a title="&quot;x&quot; &amp; y">&lt;b&gt; &#65;&#x42; &apos;&bogus;</a>"#)
+                .unwrap();
+        assert_eq!(root.attribute("title"), Some("\"x\" & y"));
+        assert_eq!(root.text(), "<b> AB '&bogus;");
+    }
+
+    #[test]
+    fn keeps_cdata_and_comments() {
+        let root = parse(
+            "<?xml version=\"1.0\"?>\n<!-- before -->\n<a><!-- note --><![CDATA[<raw> & text]]></a>",
+        )
+        .unwrap();
+        assert_eq!(
+            root.children,
+            vec![
+                XmlNode::Comment(" note ".to_string()),
+                XmlNode::Text("<raw> & text".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_self_closing_tags() {
+        let root = parse("<a><b x='1'/><c /></a>").unwrap();
+        let children = root.elements().collect::<Vec<_>>();
+        assert_eq!(children.len(), 2);
+        assert_eq!(children[0].name, "b");
+        assert_eq!(children[0].attribute("x"), Some("1"));
+        assert!(children[0].children.is_empty());
+        assert_eq!(children[1].name, "c");
+    }
+
+    #[test]
+    fn rejects_mismatched_tags() {
+        assert!(parse("<a><b></a></b>").is_err());
+        assert!(parse("<a>").is_err());
+    }
+
+    #[test]
+    fn round_trips_through_to_document_string() {
+        let root = XmlElement::new("hkpackfile")
+            .with_attribute("toplevelobject", "#0001")
+            .with_child(
+                XmlElement::new("hksection")
+                    .with_attribute("name", "__data__")
+                    .with_child(
+                        XmlElement::new("hkparam")
+                            .with_attribute("name", "text")
+                            .with_text("a < b & \"c\""),
+                    )
+                    .with_child(XmlElement::new("hkparam").with_attribute("name", "empty")),
+            );
+        let written = root.to_document_string();
+        assert!(written.starts_with("<?xml"));
+        assert_eq!(parse(&written).unwrap(), root);
+    }
+}